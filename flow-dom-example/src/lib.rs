@@ -1,4 +1,5 @@
 use flow::prelude::*;
+use std::rc::Rc;
 
 pub async fn main() {
     let root = web_sys::window()
@@ -19,66 +20,96 @@ pub async fn main() {
 
     flow::dom::start_dom(
         "root",
+        Dispatcher::new(),
         TodoAppModel {
-            text_input: text_input::Model::new(),
             todos: TodoModel {
-                todos: vec![Todo {
+                todos: Rc::new(vec![Todo {
+                    id: 0,
                     text: "Learn Rust".to_string(),
                     completed: false,
-                }],
+                }]),
+                next_id: 1,
             },
             visibility_filter: VisibilityFilterModel {
                 visibility_filter: VisibilityFilter::ShowAll,
             },
         },
-        |TodoAppModel {
-             todos,
-             visibility_filter,
-             text_input,
-         }| {
-            TodoAppView {
-                todos: todos.todos.clone(),
-                visibility_filter: visibility_filter.visibility_filter,
-                text_input: text_input.map_to_view(),
+        {
+            let filtered_todos = selector(
+                |model: &TodoAppModel| {
+                    (
+                        ByAddress(model.todos.todos.clone()),
+                        model.visibility_filter.visibility_filter,
+                    )
+                },
+                |(todos, visibility_filter)| {
+                    Rc::new(
+                        todos
+                            .iter()
+                            .filter(|todo| match visibility_filter {
+                                VisibilityFilter::ShowAll => true,
+                                VisibilityFilter::ShowCompleted => todo.completed,
+                            })
+                            .cloned()
+                            .collect::<Vec<_>>(),
+                    )
+                },
+            );
+            move |model: &TodoAppModel| TodoAppView {
+                filtered_todos: filtered_todos(model),
+                visibility_filter: model.visibility_filter.visibility_filter,
+                text_input: text_input::View {},
             }
         },
+        |_| vec![],
+        FlushPolicy::AnimationFrame,
     )
     .await;
 }
 
 #[derive(PartialEq, Clone)]
 struct Todo {
+    id: usize,
     text: String,
     completed: bool,
 }
+#[derive(Reduce)]
+#[reduce(events(TodoEvent))]
 struct TodoModel {
-    todos: Vec<Todo>,
+    #[reduce(skip)]
+    todos: Rc<Vec<Todo>>,
+    #[reduce(skip)]
+    next_id: usize,
 }
 
 #[derive(PartialEq, Clone, Debug)]
 enum TodoEvent {
     AddTodo { text: String },
-    ToggleTodo { index: usize },
+    ToggleTodo { id: usize },
     Nothing,
 }
 
-impl Reduce for TodoModel {
-    fn reduce(mut self, event: &dyn std::any::Any) -> Self {
-        if let Some(event) = event.downcast_ref::<TodoEvent>() {
-            match event {
-                TodoEvent::AddTodo { text } => {
-                    flow::log!("Add todo: {}", text);
-                    self.todos.last_mut().map(|todo| todo.text += "1");
-                    self.todos.push(Todo {
-                        text: text.clone(),
-                        completed: false,
-                    });
-                }
-                TodoEvent::ToggleTodo { index } => {
-                    self.todos[*index].completed = !self.todos[*index].completed;
+impl ReduceEvent<TodoEvent> for TodoModel {
+    fn reduce_event(mut self, event: &TodoEvent) -> Self {
+        match event {
+            TodoEvent::AddTodo { text } => {
+                flow::log!("Add todo: {}", text);
+                let todos = Rc::make_mut(&mut self.todos);
+                todos.last_mut().map(|todo| todo.text += "1");
+                todos.push(Todo {
+                    id: self.next_id,
+                    text: text.clone(),
+                    completed: false,
+                });
+                self.next_id += 1;
+            }
+            TodoEvent::ToggleTodo { id } => {
+                let todos = Rc::make_mut(&mut self.todos);
+                if let Some(todo) = todos.iter_mut().find(|todo| todo.id == *id) {
+                    todo.completed = !todo.completed;
                 }
-                TodoEvent::Nothing => {}
             }
+            TodoEvent::Nothing => {}
         }
 
         self
@@ -87,89 +118,60 @@ impl Reduce for TodoModel {
 
 mod text_input {
     use super::*;
+    #[derive(Reduce)]
+    #[reduce(events(Event))]
     pub struct Model {}
 
-    impl Model {
-        pub fn new() -> Self {
-            Self {}
-        }
-        pub fn map_to_view(&self) -> View {
-            View {}
-        }
-    }
-
     pub enum Event {}
 
-    impl super::Reduce for Model {
-        fn reduce(self, event: &dyn std::any::Any) -> Self {
-            #[allow(unused_variables)]
-            if let Some(event) = event.downcast_ref::<Event>() {
-                todo!()
-            } else {
-                self
-            }
+    impl ReduceEvent<Event> for Model {
+        fn reduce_event(self, event: &Event) -> Self {
+            match *event {}
         }
     }
     #[derive(PartialEq, Clone)]
     pub struct View {}
 
-    impl Render for View {
-        fn render(self: Box<Self>) -> super::Element {
+    impl StatefulRender for View {
+        type State = Model;
+
+        fn init_state(&self) -> Model {
+            Model {}
+        }
+        fn render(&self, _: &Model, _: &LocalDispatcher) -> super::Element {
             render(())
         }
     }
 }
 
+#[derive(Reduce)]
 struct TodoAppModel {
     todos: TodoModel,
     visibility_filter: VisibilityFilterModel,
-    text_input: text_input::Model,
-}
-
-impl Reduce for TodoAppModel {
-    fn reduce(self, event: &dyn std::any::Any) -> Self {
-        TodoAppModel {
-            todos: self.todos.reduce(event),
-            visibility_filter: self.visibility_filter.reduce(event),
-            text_input: self.text_input.reduce(event),
-        }
-    }
 }
 
 // view
 
 #[derive(PartialEq, Clone)]
 struct TodoAppView {
-    todos: Vec<Todo>,
+    filtered_todos: Rc<Vec<Todo>>,
     visibility_filter: VisibilityFilter,
     text_input: text_input::View,
 }
 
 impl Render for TodoAppView {
-    fn render(self: Box<Self>) -> Element {
+    fn render(&self) -> Element {
         flow::log!("TodoAppView render called");
-        let filtered_todos = self
-            .todos
-            .iter()
-            .filter(|todo| match self.visibility_filter {
-                VisibilityFilter::ShowAll => true,
-                VisibilityFilter::ShowCompleted => todo.completed,
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        flow::log!("todos: {:?}", self.todos.len());
-        flow::log!("filtered_todos: {:?}", filtered_todos.len());
-
-        render((
-            TodoListView {
-                todos: filtered_todos,
-            },
-            VisibilityFilterView {
-                visibility_filter: self.visibility_filter,
-            },
-            self.text_input,
-        ))
+        flow::log!("filtered_todos: {:?}", self.filtered_todos.len());
+
+        rsx! {
+            {error_boundary(
+                "Could not show the todos",
+                TodoListView { todos: self.filtered_todos.clone() },
+            )}
+            <VisibilityFilterView visibility_filter={self.visibility_filter} />
+            {self.text_input.clone()}
+        }
     }
     fn on_mount(&self) {
         flow::log!("TodoAppView mounted");
@@ -180,24 +182,23 @@ impl Render for TodoAppView {
 }
 #[derive(PartialEq, Clone)]
 struct TodoListView {
-    todos: Vec<Todo>,
+    todos: Rc<Vec<Todo>>,
 }
 
 impl Render for TodoListView {
-    fn render(self: Box<Self>) -> Element {
-        let mut elements = vec![];
-
-        for (index, todo) in self.todos.iter().enumerate() {
-            elements.push(TodoView {
-                text: todo.text.clone(),
-                completed: todo.completed,
-                index,
-            });
-        }
-
-        flow::log!("TodoListView rendered: {} todos", elements.len());
-
-        render(elements)
+    fn render(&self) -> Element {
+        flow::log!("TodoListView rendered: {} todos", self.todos.len());
+
+        render(self.todos.iter().map(|todo| {
+            Element::keyed(
+                todo.id,
+                TodoView {
+                    text: todo.text.clone(),
+                    completed: todo.completed,
+                    id: todo.id,
+                },
+            )
+        }))
     }
     fn on_mount(&self) {
         flow::log!("TodoListView mounted");
@@ -207,56 +208,34 @@ impl Render for TodoListView {
     }
 }
 
-#[derive(PartialEq, Clone)]
-struct TodoView {
-    text: String,
-    completed: bool,
-    index: usize,
+fn todo_view_mounted(_: &TodoView) {
+    flow::log!("TodoView mounted");
+}
+fn todo_view_unmounted(_: &TodoView) {
+    flow::log!("TodoView unmounted");
 }
 
-impl Render for TodoView {
-    fn render(self: Box<Self>) -> Element {
-        let style = HtmlStyle {
-            text_decoration: if self.completed {
-                Some(TextDecoration::LineThrough)
-            } else {
-                None
-            },
-            ..default()
-        };
-
-        li(
-            (style, on_click(TodoEvent::ToggleTodo { index: self.index })),
-            self.text,
-        )
+#[component(on_mount = todo_view_mounted, on_unmount = todo_view_unmounted)]
+fn todo_view(text: String, completed: bool, id: usize) -> Element {
+    let style = HtmlStyle {
+        text_decoration: if *completed {
+            Some(TextDecoration::LineThrough)
+        } else {
+            None
+        },
+        ..default()
+    };
 
-        /* React version
-        <li
-            key={index}
-            style={{ textDecoration: todo.completed ? 'line-through' : 'none' }}
-            onClick={() => toggleTodo(index)}
-        >
-            {todo.text}
+    rsx! {
+        <li style={style} on_click={TodoEvent::ToggleTodo { id: *id }}>
+            {text}
         </li>
-        */
-    }
-    fn on_mount(&self) {
-        flow::log!("TodoView mounted");
-    }
-    fn on_unmount(&self) {
-        flow::log!("TodoView unmounted");
     }
 }
 
-#[derive(PartialEq, Clone)]
-struct VisibilityFilterView {
-    visibility_filter: VisibilityFilter,
-}
-
-impl Render for VisibilityFilterView {
-    fn render(self: Box<Self>) -> Element {
-        render(())
-    }
+#[component]
+fn visibility_filter_view(visibility_filter: VisibilityFilter) -> Element {
+    render(())
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -265,7 +244,10 @@ enum VisibilityFilter {
     ShowCompleted,
 }
 
+#[derive(Reduce)]
+#[reduce(events(VisibilityFilterEvent))]
 struct VisibilityFilterModel {
+    #[reduce(skip)]
     visibility_filter: VisibilityFilter,
 }
 
@@ -273,18 +255,12 @@ enum VisibilityFilterEvent {
     SetVisibilityFilter(VisibilityFilter),
 }
 
-impl Reduce for VisibilityFilterModel {
-    fn reduce(self, event: &dyn std::any::Any) -> Self {
-        if let Some(event) = event.downcast_ref::<VisibilityFilterEvent>() {
-            match event {
-                VisibilityFilterEvent::SetVisibilityFilter(visibility_filter) => {
-                    VisibilityFilterModel {
-                        visibility_filter: *visibility_filter,
-                    }
-                }
-            }
-        } else {
-            self
+impl ReduceEvent<VisibilityFilterEvent> for VisibilityFilterModel {
+    fn reduce_event(self, event: &VisibilityFilterEvent) -> Self {
+        match event {
+            VisibilityFilterEvent::SetVisibilityFilter(visibility_filter) => VisibilityFilterModel {
+                visibility_filter: *visibility_filter,
+            },
         }
     }
 }
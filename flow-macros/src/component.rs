@@ -0,0 +1,142 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    FnArg, Ident, ItemFn, Pat, Path, Token,
+};
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<ComponentArgs>(args)?;
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = syn::parse2::<ItemFn>(item)?;
+
+    if let Some(asyncness) = sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "components cannot be async",
+        ));
+    }
+
+    let name = format_ident!(
+        "{}",
+        pascal_case(&sig.ident.to_string()),
+        span = sig.ident.span()
+    );
+    let mut fields = vec![];
+    let mut bindings = vec![];
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new(
+                input.span(),
+                "components take props, not `self`",
+            ));
+        };
+        let Pat::Ident(pat_ident) = arg.pat.as_ref() else {
+            return Err(syn::Error::new(
+                arg.pat.span(),
+                "component props must be plain identifiers",
+            ));
+        };
+        if pat_ident.by_ref.is_some() || pat_ident.mutability.is_some() {
+            return Err(syn::Error::new(
+                pat_ident.span(),
+                "component props are borrowed from the component and cannot be `ref` or `mut`",
+            ));
+        }
+        let field_attrs = &arg.attrs;
+        let field_name = &pat_ident.ident;
+        let ty = &arg.ty;
+        fields.push(quote! { #(#field_attrs)* pub #field_name: #ty });
+        bindings.push(pat_ident);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = sig.generics.split_for_impl();
+    let generics = &sig.generics;
+    let output = &sig.output;
+    let on_mount = args.on_mount.map(|on_mount| {
+        quote! {
+            fn on_mount(&self) {
+                #on_mount(self)
+            }
+        }
+    });
+    let on_unmount = args.on_unmount.map(|on_unmount| {
+        quote! {
+            fn on_unmount(&self) {
+                #on_unmount(self)
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(Clone, PartialEq)]
+        #vis struct #name #generics #where_clause {
+            #(#fields,)*
+        }
+
+        impl #impl_generics ::flow::Render for #name #ty_generics #where_clause {
+            fn render(&self) #output {
+                // Props may be read only by `on_mount` or `on_unmount`.
+                #[allow(unused_variables)]
+                let #name { #(#bindings,)* } = self;
+                #block
+            }
+            #on_mount
+            #on_unmount
+        }
+    })
+}
+
+#[derive(Default)]
+struct ComponentArgs {
+    on_mount: Option<Path>,
+    on_unmount: Option<Path>,
+}
+
+impl Parse for ComponentArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = ComponentArgs::default();
+        let pairs =
+            Punctuated::<(Ident, Path), Token![,]>::parse_terminated_with(input, |input| {
+                let key = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                Ok((key, input.parse()?))
+            })?;
+        for (key, path) in pairs {
+            let slot = match key.to_string().as_str() {
+                "on_mount" => &mut args.on_mount,
+                "on_unmount" => &mut args.on_unmount,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `on_mount` or `on_unmount`",
+                    ))
+                }
+            };
+            if slot.replace(path).is_some() {
+                return Err(syn::Error::new(key.span(), format!("duplicate `{key}`")));
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn pascal_case(snake_case: &str) -> String {
+    snake_case
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
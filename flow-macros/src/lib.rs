@@ -0,0 +1,76 @@
+mod component;
+mod reduce;
+mod rsx;
+
+use proc_macro::TokenStream;
+
+/// Builds an `Element` from markup, expanding into the `flow` element constructors.
+///
+/// ```ignore
+/// rsx! {
+///     <ul class="todos">
+///         for (index, todo) in todos.iter().enumerate() {
+///             <li key={index} on_click={TodoEvent::Toggle { index }}>{todo.text.clone()}</li>
+///         }
+///         if todos.is_empty() {
+///             <TodoPlaceholder text={"Nothing to do"} />
+///         }
+///     </ul>
+/// }
+/// ```
+///
+/// - A lowercase tag calls the element constructor of the same name, such as `flow::li`.
+///   Each attribute calls the prop constructor of its name with its value, so `class="a"` is
+///   `flow::class("a")`. `style` takes an `HtmlStyle` as is, an attribute without a value is a
+///   boolean attribute set to `true`, and `key` wraps the element in `Element::keyed`.
+/// - Any other tag is a component struct that implements `Render`, built from its attributes
+///   as fields.
+/// - Children are nodes, string literals, `{expr}` of anything `IntoElement`, `if` blocks and
+///   `for` blocks.
+#[proc_macro]
+pub fn rsx(input: TokenStream) -> TokenStream {
+    rsx::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turns a function of props into a component struct that implements `Render`.
+///
+/// ```ignore
+/// #[component(on_mount = todo_view_mounted)]
+/// fn todo_view(text: String, completed: bool) -> Element {
+///     rsx! { <li>{text}</li> }
+/// }
+/// ```
+///
+/// generates `#[derive(Clone, PartialEq)] struct TodoView { pub text: String, pub completed: bool }`
+/// and a `Render` impl whose `render` runs the function body with each prop bound by name
+/// to a reference into the component.
+/// `on_mount` and `on_unmount` name functions that take `&TodoView`.
+#[proc_macro_attribute]
+pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
+    component::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `Reduce` for a struct by handling its own events and then forwarding to its fields.
+///
+/// ```ignore
+/// #[derive(Reduce)]
+/// #[reduce(events(TodoEvent))]
+/// struct TodoAppModel {
+///     todos: TodoModel,
+///     #[reduce(skip)]
+///     next_id: usize,
+/// }
+/// ```
+///
+/// Each type listed in `events(..)` is downcast from the event and passed to the struct's
+/// `ReduceEvent<E>` impl. Then every field not marked `#[reduce(skip)]` is reduced in turn.
+#[proc_macro_derive(Reduce, attributes(reduce))]
+pub fn derive_reduce(input: TokenStream) -> TokenStream {
+    reduce::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
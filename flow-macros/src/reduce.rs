@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Fields, Index, Member, Token, Type,
+};
+
+pub(crate) fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`Reduce` can only be derived for structs",
+        ));
+    };
+
+    let mut events = vec![];
+    for attr in &input.attrs {
+        if !attr.path().is_ident("reduce") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("events") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                events.extend(Punctuated::<Type, Token![,]>::parse_terminated(&content)?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `events(..)`"))
+            }
+        })?;
+    }
+
+    let mut members = vec![];
+    let mut bindings = vec![];
+    let mut values = vec![];
+    for (index, field) in data.fields.iter().enumerate() {
+        let mut skip = false;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("reduce") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip`"))
+                }
+            })?;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let binding = format_ident!("field_{}", index);
+        values.push(if skip {
+            quote! { #binding }
+        } else {
+            quote! { ::flow::Reduce::reduce(#binding, event) }
+        });
+        members.push(member);
+        bindings.push(binding);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let reduce_fields = match data.fields {
+        Fields::Unit => quote! { this },
+        _ => quote! {
+            let #name { #(#members: #bindings,)* } = this;
+            #name { #(#members: #values,)* }
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::flow::Reduce for #name #ty_generics #where_clause {
+            fn reduce(self, event: &dyn ::core::any::Any) -> Self {
+                #[allow(unused_mut)]
+                let mut this = self;
+                #(
+                    if let Some(event) = event.downcast_ref::<#events>() {
+                        this = ::flow::ReduceEvent::<#events>::reduce_event(this, event);
+                    }
+                )*
+                #reduce_fields
+            }
+        }
+    })
+}
@@ -0,0 +1,372 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    braced,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    token, Expr, Ident, LitStr, Pat, Path, Token,
+};
+
+/// Tuples implement `IntoElement` and `ElementProps` up to this many members.
+const MAX_TUPLE_LEN: usize = 12;
+
+pub(crate) fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let nodes = syn::parse2::<Nodes>(input)?;
+    Ok(quote! { ::flow::render(#nodes) })
+}
+
+struct Nodes(Vec<Node>);
+
+enum Node {
+    Element(ElementNode),
+    Text(LitStr),
+    Expr(Expr),
+    If(IfNode),
+    For(ForNode),
+}
+
+struct ElementNode {
+    path: Path,
+    attributes: Vec<Attribute>,
+    children: Nodes,
+}
+
+struct Attribute {
+    name: String,
+    span: Span,
+    value: Option<Expr>,
+}
+
+struct IfNode {
+    condition: Expr,
+    then_branch: Nodes,
+    else_branch: Option<ElseBranch>,
+}
+
+enum ElseBranch {
+    If(Box<IfNode>),
+    Nodes(Nodes),
+}
+
+struct ForNode {
+    pat: Pat,
+    iter: Expr,
+    body: Nodes,
+}
+
+impl Parse for Nodes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut nodes = vec![];
+        let at_closing_tag = |input: ParseStream| input.peek(Token![<]) && input.peek2(Token![/]);
+        while !input.is_empty() && !at_closing_tag(input) {
+            nodes.push(input.parse()?);
+        }
+        Ok(Nodes(nodes))
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![<]) {
+            input.parse().map(Node::Element)
+        } else if lookahead.peek(LitStr) {
+            input.parse().map(Node::Text)
+        } else if lookahead.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            content.parse().map(Node::Expr)
+        } else if lookahead.peek(Token![if]) {
+            input.parse().map(Node::If)
+        } else if lookahead.peek(Token![for]) {
+            input.parse().map(Node::For)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for ElementNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![<]>()?;
+        let path = Path::parse_mod_style(input)?;
+
+        let mut attributes = vec![];
+        while !input.peek(Token![/]) && !input.peek(Token![>]) {
+            attributes.push(input.parse()?);
+        }
+
+        if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            return Ok(ElementNode {
+                path,
+                attributes,
+                children: Nodes(vec![]),
+            });
+        }
+
+        input.parse::<Token![>]>()?;
+        let children = input.parse()?;
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let closing_path = Path::parse_mod_style(input)?;
+        if closing_path.to_token_stream().to_string() != path.to_token_stream().to_string() {
+            return Err(syn::Error::new(
+                closing_path.span(),
+                format!(
+                    "expected closing tag `</{}>`",
+                    path.to_token_stream().to_string().replace(' ', "")
+                ),
+            ));
+        }
+        input.parse::<Token![>]>()?;
+
+        Ok(ElementNode {
+            path,
+            attributes,
+            children,
+        })
+    }
+}
+
+impl Parse for Attribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let first = Ident::parse_any(input)?;
+        let span = first.span();
+        let mut name = first.to_string();
+        while input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            name.push('-');
+            name.push_str(&Ident::parse_any(input)?.to_string());
+        }
+
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            if input.peek(token::Brace) {
+                let content;
+                braced!(content in input);
+                Some(content.parse()?)
+            } else {
+                let lit = input.parse::<LitStr>()?;
+                Some(syn::parse_quote!(#lit))
+            }
+        } else {
+            None
+        };
+
+        Ok(Attribute { name, span, value })
+    }
+}
+
+impl Parse for IfNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![if]>()?;
+        let condition = Expr::parse_without_eager_brace(input)?;
+        let then_branch = parse_block(input)?;
+        let else_branch = if input.peek(Token![else]) {
+            input.parse::<Token![else]>()?;
+            if input.peek(Token![if]) {
+                Some(ElseBranch::If(Box::new(input.parse()?)))
+            } else {
+                Some(ElseBranch::Nodes(parse_block(input)?))
+            }
+        } else {
+            None
+        };
+
+        Ok(IfNode {
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+}
+
+impl Parse for ForNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![for]>()?;
+        let pat = Pat::parse_multi_with_leading_vert(input)?;
+        input.parse::<Token![in]>()?;
+        let iter = Expr::parse_without_eager_brace(input)?;
+        let body = parse_block(input)?;
+
+        Ok(ForNode { pat, iter, body })
+    }
+}
+
+fn parse_block(input: ParseStream) -> syn::Result<Nodes> {
+    let content;
+    braced!(content in input);
+    content.parse()
+}
+
+impl ToTokens for Nodes {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let children = self.0.iter().map(ToTokens::to_token_stream).collect();
+        tokens.extend(tuple(children));
+    }
+}
+
+impl ToTokens for Node {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Node::Element(element) => element.to_tokens(tokens),
+            Node::Text(text) => text.to_tokens(tokens),
+            Node::Expr(expr) => tokens.extend(quote! { (#expr) }),
+            Node::If(if_node) => if_node.to_tokens(tokens),
+            Node::For(for_node) => for_node.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for ElementNode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let element = match self.path.get_ident() {
+            Some(tag)
+                if tag
+                    .to_string()
+                    .starts_with(|c: char| c.is_ascii_lowercase()) =>
+            {
+                self.html_element(tag)
+            }
+            _ => self.component(),
+        };
+
+        let key = self
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == "key")
+            .map(Attribute::value);
+        tokens.extend(match key {
+            Some(key) => quote! { ::flow::Element::keyed(#key, #element) },
+            None => element,
+        });
+    }
+}
+
+impl ElementNode {
+    fn html_element(&self, tag: &Ident) -> TokenStream {
+        let props = tuple(
+            self.attributes
+                .iter()
+                .filter(|attribute| attribute.name != "key")
+                .map(Attribute::prop)
+                .collect(),
+        );
+
+        if tag == "input" || tag == "img" {
+            if let Some(child) = self.children.0.first() {
+                return syn::Error::new(
+                    child.span(),
+                    format!("`<{tag}>` is a void element and takes no children"),
+                )
+                .into_compile_error();
+            }
+            return quote! { ::flow::#tag(#props) };
+        }
+
+        let children = &self.children;
+        quote! { ::flow::#tag(#props, #children) }
+    }
+
+    fn component(&self) -> TokenStream {
+        if let Some(child) = self.children.0.first() {
+            return syn::Error::new(child.span(), "components take no children")
+                .into_compile_error();
+        }
+
+        let path = &self.path;
+        let fields = self
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.name != "key")
+            .map(|attribute| {
+                let field = Ident::new(&attribute.name, attribute.span);
+                let value = attribute.value();
+                quote! { #field: #value }
+            });
+        quote! { #path { #(#fields),* } }
+    }
+}
+
+impl Attribute {
+    fn value(&self) -> TokenStream {
+        match &self.value {
+            Some(value) => value.to_token_stream(),
+            None => quote_spanned! { self.span => true },
+        }
+    }
+
+    fn prop(&self) -> TokenStream {
+        let value = self.value();
+        let constructor = match self.name.as_str() {
+            "style" => return value,
+            "for" => "html_for",
+            "type" => "input_type",
+            name if name.contains('-') => {
+                let name = &self.name;
+                return quote_spanned! { self.span => ::flow::attribute(#name, #value) };
+            }
+            name => name,
+        };
+        let constructor = Ident::new(constructor, self.span);
+        quote! { ::flow::#constructor(#value) }
+    }
+}
+
+impl ToTokens for IfNode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let IfNode {
+            condition,
+            then_branch,
+            else_branch,
+        } = self;
+        let else_branch = match else_branch {
+            Some(ElseBranch::If(if_node)) => if_node.to_token_stream(),
+            Some(ElseBranch::Nodes(nodes)) => quote! { ::flow::render(#nodes) },
+            None => quote! { ::flow::render(()) },
+        };
+        tokens.extend(quote! {
+            if #condition { ::flow::render(#then_branch) } else { #else_branch }
+        });
+    }
+}
+
+impl ToTokens for ForNode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ForNode { pat, iter, body } = self;
+        tokens.extend(quote! {
+            ::flow::fragment(
+                ::core::iter::IntoIterator::into_iter(#iter).map(|#pat| ::flow::render(#body))
+            )
+        });
+    }
+}
+
+impl Node {
+    fn span(&self) -> Span {
+        match self {
+            Node::Element(element) => element.path.span(),
+            Node::Text(text) => text.span(),
+            Node::Expr(expr) => expr.span(),
+            Node::If(if_node) => if_node.condition.span(),
+            Node::For(for_node) => for_node.pat.span(),
+        }
+    }
+}
+
+/// Groups `items` into a tuple, nesting tuples when there are more than `MAX_TUPLE_LEN`.
+fn tuple(mut items: Vec<TokenStream>) -> TokenStream {
+    while items.len() > MAX_TUPLE_LEN {
+        items = items
+            .chunks(MAX_TUPLE_LEN)
+            .map(|chunk| tuple(chunk.to_vec()))
+            .collect();
+    }
+    match items.as_slice() {
+        [item] => item.clone(),
+        items => quote! { (#(#items),*) },
+    }
+}
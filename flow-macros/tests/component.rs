@@ -0,0 +1,108 @@
+use flow::{dom::headless::HeadlessApp, prelude::*};
+use std::{any::Any, cell::RefCell};
+
+struct Model {
+    count: usize,
+}
+
+struct Increment;
+
+impl Reduce for Model {
+    fn reduce(self, event: &dyn Any) -> Self {
+        match event.downcast_ref::<Increment>() {
+            Some(Increment) => Model {
+                count: self.count + 1,
+            },
+            None => self,
+        }
+    }
+}
+
+thread_local! {
+    static LIFECYCLE: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+fn counter_mounted(counter: &CounterLabel) {
+    LIFECYCLE.with(|lifecycle| {
+        lifecycle
+            .borrow_mut()
+            .push(format!("mount {}", counter.count))
+    });
+}
+
+fn counter_unmounted(counter: &CounterLabel) {
+    LIFECYCLE.with(|lifecycle| {
+        lifecycle
+            .borrow_mut()
+            .push(format!("unmount {}", counter.count))
+    });
+}
+
+/// Shows the count.
+#[component(on_mount = counter_mounted, on_unmount = counter_unmounted)]
+fn counter_label(count: usize, prefix: &'static str) -> Element {
+    rsx! { <span>{format!("{prefix}{count}")}</span> }
+}
+
+#[derive(Clone, PartialEq)]
+struct CounterView {
+    count: usize,
+}
+
+impl Render for CounterView {
+    fn render(&self) -> Element {
+        rsx! {
+            if self.count < 2 {
+                <CounterLabel count={self.count} prefix={"#"} />
+            }
+        }
+    }
+}
+
+#[test]
+fn props_become_public_fields_compared_by_value() {
+    let label = CounterLabel {
+        count: 1,
+        prefix: "#",
+    };
+    assert!(label.clone() == label);
+    assert!(
+        label
+            != CounterLabel {
+                count: 2,
+                prefix: "#",
+            }
+    );
+}
+
+#[test]
+fn render_runs_the_body_and_hooks_get_the_component() {
+    let mut app = HeadlessApp::new(Model { count: 0 }, |model: &Model| CounterView {
+        count: model.count,
+    });
+    assert_eq!(app.html(), "<span>#0</span>");
+
+    app.dispatch(Increment);
+    app.flush();
+    assert_eq!(app.html(), "<span>#1</span>");
+
+    app.dispatch(Increment);
+    app.flush();
+    assert_eq!(app.html(), "");
+    assert_eq!(
+        LIFECYCLE.with(|lifecycle| lifecycle.borrow().clone()),
+        ["mount 0", "unmount 1"]
+    );
+}
+
+#[component]
+fn generic_label<T: ToString + Clone + PartialEq + 'static>(value: T) -> Element {
+    rsx! { <span>{value.to_string()}</span> }
+}
+
+#[test]
+fn generic_props_are_kept_on_the_struct() {
+    let label = GenericLabel { value: 3 };
+    let app = HeadlessApp::new(Model { count: 0 }, move |_: &Model| label.clone());
+    assert_eq!(app.html(), "<span>3</span>");
+}
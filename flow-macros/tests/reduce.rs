@@ -0,0 +1,119 @@
+use flow::prelude::*;
+use std::any::Any;
+
+struct Increment;
+
+#[derive(Debug, PartialEq)]
+struct CounterModel {
+    count: usize,
+}
+
+impl Reduce for CounterModel {
+    fn reduce(self, event: &dyn Any) -> Self {
+        match event.downcast_ref::<Increment>() {
+            Some(Increment) => CounterModel {
+                count: self.count + 1,
+            },
+            None => self,
+        }
+    }
+}
+
+struct RemoveItem(usize);
+
+#[derive(Debug, PartialEq, Reduce)]
+#[reduce(events(RemoveItem))]
+struct ListModel {
+    #[reduce(skip)]
+    items: Vec<usize>,
+}
+
+impl ReduceEvent<RemoveItem> for ListModel {
+    fn reduce_event(mut self, RemoveItem(item): &RemoveItem) -> Self {
+        self.items.retain(|existing| existing != item);
+        self
+    }
+}
+
+#[derive(Reduce)]
+struct AppModel {
+    counter: CounterModel,
+    list: ListModel,
+}
+
+#[test]
+fn derived_reduce_forwards_events_to_fields() {
+    let model = AppModel {
+        counter: CounterModel { count: 0 },
+        list: ListModel { items: vec![1, 2] },
+    };
+
+    let model = model.reduce(&Increment).reduce(&RemoveItem(1));
+
+    assert_eq!(model.counter.count, 1);
+    assert_eq!(model.list.items, vec![2]);
+}
+
+struct Reset;
+
+/// Handles its own events before its fields, and leaves the skipped field to them.
+#[derive(Reduce)]
+#[reduce(events(Reset, Increment))]
+struct ScoreModel {
+    current: CounterModel,
+    #[reduce(skip)]
+    best: CounterModel,
+}
+
+impl ReduceEvent<Reset> for ScoreModel {
+    fn reduce_event(self, _: &Reset) -> Self {
+        ScoreModel {
+            current: CounterModel { count: 0 },
+            best: self.best,
+        }
+    }
+}
+
+impl ReduceEvent<Increment> for ScoreModel {
+    fn reduce_event(self, _: &Increment) -> Self {
+        let best = self.current.count + 1;
+        ScoreModel {
+            best: CounterModel {
+                count: self.best.count.max(best),
+            },
+            ..self
+        }
+    }
+}
+
+#[test]
+fn own_events_run_first_and_skipped_fields_are_not_reduced() {
+    let model = ScoreModel {
+        current: CounterModel { count: 0 },
+        best: CounterModel { count: 0 },
+    };
+
+    let model = model.reduce(&Increment).reduce(&Increment);
+    assert_eq!(model.current, CounterModel { count: 2 });
+    assert_eq!(model.best, CounterModel { count: 2 });
+
+    let model = model.reduce(&Reset).reduce(&Increment);
+    assert_eq!(model.current, CounterModel { count: 1 });
+    assert_eq!(model.best, CounterModel { count: 2 });
+}
+
+#[derive(Reduce)]
+struct Pair(CounterModel, #[reduce(skip)] CounterModel);
+
+#[derive(Reduce)]
+struct Empty;
+
+#[test]
+fn tuple_and_unit_structs_are_supported() {
+    let Pair(first, second) =
+        Pair(CounterModel { count: 0 }, CounterModel { count: 0 }).reduce(&Increment);
+    assert_eq!(first.count, 1);
+    assert_eq!(second.count, 0);
+
+    let Empty = Empty.reduce(&Increment);
+}
@@ -0,0 +1,80 @@
+use flow::{dom::headless::HeadlessApp, prelude::*};
+use std::any::Any;
+
+struct Model;
+
+impl Reduce for Model {
+    fn reduce(self, _event: &dyn Any) -> Self {
+        self
+    }
+}
+
+fn html(view: impl Render + PartialEq + Clone + 'static) -> String {
+    HeadlessApp::new(Model, move |_: &Model| view.clone()).html()
+}
+
+#[derive(Clone, PartialEq)]
+struct ManyChildren;
+
+impl Render for ManyChildren {
+    fn render(&self) -> Element {
+        rsx! {
+            <ul>
+                "0" "1" "2" "3" "4" "5" "6" "7" "8" "9" "10" "11" "12"
+                <li>"13"</li>
+            </ul>
+        }
+    }
+}
+
+#[test]
+fn more_than_twelve_children_are_nested_in_order() {
+    assert_eq!(html(ManyChildren), "<ul>0123456789101112<li>13</li></ul>");
+}
+
+#[derive(Clone, PartialEq)]
+struct ManyAttributes;
+
+impl Render for ManyAttributes {
+    fn render(&self) -> Element {
+        rsx! {
+            <div
+                id="d" class="c" data-a="a" data-b="b" data-c="c" data-d="d" data-e="e"
+                data-f="f" data-g="g" data-h="h" data-i="i" data-j="j" data-k="k"
+            />
+        }
+    }
+}
+
+#[test]
+fn more_than_twelve_attributes_are_nested_in_order() {
+    assert_eq!(
+        html(ManyAttributes),
+        "<div class=\"c\" data-a=\"a\" data-b=\"b\" data-c=\"c\" data-d=\"d\" data-e=\"e\" \
+         data-f=\"f\" data-g=\"g\" data-h=\"h\" data-i=\"i\" data-j=\"j\" data-k=\"k\" \
+         id=\"d\"></div>"
+    );
+}
+
+#[derive(Clone, PartialEq)]
+struct Root;
+
+impl Render for Root {
+    fn render(&self) -> Element {
+        let items = [1, 2];
+        rsx! {
+            for item in items {
+                if item == 1 {
+                    <li key={item}>"one"</li>
+                } else {
+                    {item.to_string()}
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn for_and_if_blocks_expand_to_fragments() {
+    assert_eq!(html(Root), "<li>one</li>2");
+}
@@ -0,0 +1,8 @@
+use flow::prelude::*;
+
+#[component]
+async fn label(text: String) -> Element {
+    render(())
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use flow::prelude::*;
+
+fn mounted(_: &Label) {}
+
+#[component(on_mount = mounted, on_mount = mounted)]
+fn label(text: String) -> Element {
+    render(())
+}
+
+fn main() {}
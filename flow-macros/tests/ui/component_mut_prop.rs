@@ -0,0 +1,8 @@
+use flow::prelude::*;
+
+#[component]
+fn label(mut text: String) -> Element {
+    render(())
+}
+
+fn main() {}
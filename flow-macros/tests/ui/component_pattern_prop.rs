@@ -0,0 +1,8 @@
+use flow::prelude::*;
+
+#[component]
+fn label((text, count): (String, usize)) -> Element {
+    render(())
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use flow::prelude::*;
+
+fn mounted(_: &Label) {}
+
+#[component(on_render = mounted)]
+fn label(text: String) -> Element {
+    render(())
+}
+
+fn main() {}
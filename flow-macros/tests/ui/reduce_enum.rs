@@ -0,0 +1,8 @@
+use flow::prelude::*;
+
+#[derive(Reduce)]
+enum Model {
+    Empty,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use flow::prelude::*;
+
+struct Reset;
+
+#[derive(Reduce)]
+#[reduce(events(Reset))]
+struct Model {}
+
+fn main() {}
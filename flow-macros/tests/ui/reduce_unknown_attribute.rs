@@ -0,0 +1,7 @@
+use flow::prelude::*;
+
+#[derive(Reduce)]
+#[reduce(event(String))]
+struct Model {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use flow::prelude::*;
+
+#[derive(Reduce)]
+struct Model {
+    #[reduce(ignore)]
+    count: usize,
+}
+
+fn main() {}
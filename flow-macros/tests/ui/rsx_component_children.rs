@@ -0,0 +1,14 @@
+use flow::prelude::*;
+
+#[derive(Clone, PartialEq)]
+struct Label;
+
+impl Render for Label {
+    fn render(&self) -> Element {
+        render(())
+    }
+}
+
+fn main() {
+    let _ = rsx! { <Label>"text"</Label> };
+}
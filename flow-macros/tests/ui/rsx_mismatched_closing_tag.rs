@@ -0,0 +1,5 @@
+use flow::prelude::*;
+
+fn main() {
+    let _ = rsx! { <ul><li>"a"</ul></li> };
+}
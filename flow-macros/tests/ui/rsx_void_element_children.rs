@@ -0,0 +1,5 @@
+use flow::prelude::*;
+
+fn main() {
+    let _ = rsx! { <input>"text"</input> };
+}
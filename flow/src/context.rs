@@ -0,0 +1,249 @@
+use crate::{current_slot, Element, IntoElement, LocalStateSlot, Render};
+use std::{
+    any::Any,
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+/// Makes `value` readable with `use_context` by every component rendered under `children`.
+///
+/// When the provider re-renders with a different value, the components that read it re-render
+/// as well, even if the props of the components between them are equal.
+pub fn provide_context<T: Clone + PartialEq + 'static>(
+    value: T,
+    children: impl IntoElement,
+) -> Element {
+    Element::single(Box::new(ContextProvider {
+        value,
+        children: children.into_element(),
+    }))
+}
+
+/// The value of type `T` provided by the nearest ancestor, if any.
+///
+/// Only meaningful inside `render`, where it subscribes the rendering component to the value.
+pub fn use_context<T: Clone + 'static>() -> Option<T> {
+    let Some(slot) = current_slot() else {
+        crate::error!("`use_context` called outside of render");
+        return None;
+    };
+    let mut scope = slot.contexts.clone();
+    while let Some(current) = scope {
+        if let Some(value) = current.value.borrow().downcast_ref::<T>() {
+            current.subscribe(&slot);
+            return Some(value.clone());
+        }
+        scope = current.parent.clone();
+    }
+    None
+}
+
+#[derive(Clone, PartialEq)]
+struct ContextProvider<T> {
+    value: T,
+    children: Element,
+}
+
+impl<T: Clone + PartialEq + 'static> Render for ContextProvider<T> {
+    fn render(&self) -> Element {
+        match current_slot() {
+            Some(slot) => slot.provide(&self.value),
+            None => crate::error!("`ContextProvider` rendered outside of the render tree"),
+        }
+        self.children.clone()
+    }
+}
+
+/// A value provided by one node, linked to the values provided above it.
+pub(crate) struct ContextScope {
+    value: RefCell<Box<dyn Any>>,
+    consumers: RefCell<Vec<Weak<LocalStateSlot>>>,
+    parent: Option<Rc<ContextScope>>,
+}
+
+impl ContextScope {
+    fn subscribe(&self, slot: &Rc<LocalStateSlot>) {
+        let mut consumers = self.consumers.borrow_mut();
+        consumers.retain(|consumer| consumer.strong_count() > 0);
+        if !consumers
+            .iter()
+            .any(|consumer| std::ptr::eq(consumer.as_ptr(), Rc::as_ptr(slot)))
+        {
+            consumers.push(Rc::downgrade(slot));
+        }
+    }
+}
+
+impl LocalStateSlot {
+    /// The contexts visible to the children of this node.
+    pub(crate) fn child_contexts(&self) -> Option<Rc<ContextScope>> {
+        self.provided
+            .borrow()
+            .clone()
+            .or_else(|| self.contexts.clone())
+    }
+
+    /// Provides `value` to the children of this node,
+    /// marking the components that read the previous value as changed.
+    fn provide<T: Clone + PartialEq + 'static>(&self, value: &T) {
+        let mut provided = self.provided.borrow_mut();
+        let Some(scope) = provided.as_ref() else {
+            *provided = Some(Rc::new(ContextScope {
+                value: RefCell::new(Box::new(value.clone())),
+                consumers: RefCell::new(vec![]),
+                parent: self.contexts.clone(),
+            }));
+            return;
+        };
+        if scope.value.borrow().downcast_ref::<T>() == Some(value) {
+            return;
+        }
+        *scope.value.borrow_mut() = Box::new(value.clone());
+        for consumer in scope.consumers.borrow().iter() {
+            if let Some(consumer) = consumer.upgrade() {
+                consumer.mark_dirty();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dom::headless::HeadlessApp, Dispatcher};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Locale(&'static str);
+
+    /// Renders a provider of `value` in `slot`, as the render tree does for a node.
+    fn provide<T: Clone + PartialEq + 'static>(slot: &Rc<LocalStateSlot>, value: T) {
+        slot.render(&Dispatcher::new(), || {
+            Render::render(&ContextProvider {
+                value,
+                children: ().into_element(),
+            })
+        });
+    }
+
+    fn child_of(slot: &LocalStateSlot) -> Rc<LocalStateSlot> {
+        Rc::new(LocalStateSlot::new(slot.child_contexts()))
+    }
+
+    fn read<T: Clone + 'static>(slot: &Rc<LocalStateSlot>) -> Option<T> {
+        slot.render(&Dispatcher::new(), use_context::<T>)
+    }
+
+    #[test]
+    fn reads_the_value_of_the_nearest_provider_of_its_type() {
+        let root = Rc::new(LocalStateSlot::new(None));
+        provide(&root, Theme("dark"));
+        let middle = child_of(&root);
+        provide(&middle, Locale("en"));
+        let inner = child_of(&middle);
+        provide(&inner, Theme("light"));
+
+        let consumer = child_of(&inner);
+        assert_eq!(read(&consumer), Some(Theme("light")));
+        assert_eq!(read(&consumer), Some(Locale("en")));
+        assert_eq!(read(&child_of(&middle)), Some(Theme("dark")));
+        assert_eq!(read::<Theme>(&Rc::new(LocalStateSlot::new(None))), None);
+    }
+
+    #[test]
+    fn a_changed_value_marks_only_its_consumers_dirty() {
+        let root = Rc::new(LocalStateSlot::new(None));
+        provide(&root, Theme("dark"));
+        let consumer = child_of(&root);
+        let other = child_of(&root);
+        read::<Theme>(&consumer);
+
+        provide(&root, Theme("dark"));
+        assert!(!consumer.take_dirty());
+
+        provide(&root, Theme("light"));
+        assert!(consumer.take_dirty());
+        assert!(!other.take_dirty());
+        assert_eq!(read(&consumer), Some(Theme("light")));
+    }
+
+    #[test]
+    fn dropped_consumers_are_not_marked() {
+        let root = Rc::new(LocalStateSlot::new(None));
+        provide(&root, Theme("dark"));
+        let consumer = child_of(&root);
+        read::<Theme>(&consumer);
+        drop(consumer);
+
+        provide(&root, Theme("light"));
+        assert!(!crate::any_dirty_slot());
+    }
+
+    struct ThemeModel(&'static str);
+
+    impl crate::Reduce for ThemeModel {
+        fn reduce(self, event: &dyn Any) -> Self {
+            match event.downcast_ref::<Theme>() {
+                Some(Theme(theme)) => ThemeModel(theme),
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Themed;
+
+    impl Render for Themed {
+        fn render(&self) -> Element {
+            let theme = use_context::<Theme>().map_or("none", |Theme(theme)| theme);
+            crate::li(crate::class(theme), ())
+        }
+    }
+
+    thread_local! {
+        static LIST_RENDERS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Has no props, so it never re-renders after mounting.
+    #[derive(Clone, PartialEq)]
+    struct ThemedList;
+
+    impl Render for ThemedList {
+        fn render(&self) -> Element {
+            LIST_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            crate::render((Themed, Themed))
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ThemeView {
+        theme: &'static str,
+    }
+
+    impl Render for ThemeView {
+        fn render(&self) -> Element {
+            crate::render((provide_context(Theme(self.theme), ThemedList), Themed))
+        }
+    }
+
+    #[test]
+    fn consumers_rerender_through_components_with_equal_props() {
+        let mut app = HeadlessApp::new(ThemeModel("a"), |ThemeModel(theme): &ThemeModel| {
+            ThemeView { theme }
+        });
+        assert_eq!(
+            app.html(),
+            "<li class=\"a\"></li><li class=\"a\"></li><li class=\"none\"></li>"
+        );
+
+        app.dispatch(Theme("b"));
+        app.flush();
+        assert_eq!(
+            app.html(),
+            "<li class=\"b\"></li><li class=\"b\"></li><li class=\"none\"></li>"
+        );
+        assert_eq!(LIST_RENDERS.with(|renders| renders.get()), 1);
+    }
+}
@@ -0,0 +1,50 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+pub(crate) type Receiver = UnboundedReceiver<Box<dyn Any>>;
+
+/// Sends events into the `start` loop.
+///
+/// Create one with `Dispatcher::new`, hand clones to outside code such as WebSocket callbacks,
+/// and pass it to `start` or `start_dom`. Events dispatched before the loop starts are queued.
+#[derive(Clone)]
+pub struct Dispatcher {
+    tx: UnboundedSender<Box<dyn Any>>,
+    rx: Rc<RefCell<Option<Receiver>>>,
+}
+
+/// Sent by `Dispatcher::shutdown` to end the loop.
+pub(crate) struct Shutdown;
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx: Rc::new(RefCell::new(Some(rx))),
+        }
+    }
+    pub fn dispatch(&self, event: impl Any) {
+        self.dispatch_box(Box::new(event));
+    }
+    /// Unmounts the whole tree, stops subscriptions and ends the loop
+    /// once the events dispatched before it are handled.
+    pub fn shutdown(&self) {
+        self.dispatch(Shutdown);
+    }
+    pub(crate) fn dispatch_box(&self, event: Box<dyn Any>) {
+        if self.tx.send(event).is_err() {
+            crate::error!("Event dispatched after the start loop has ended");
+        }
+    }
+    /// The receiving end, for the one loop this dispatcher is passed to.
+    pub(crate) fn take_receiver(&self) -> Option<Receiver> {
+        self.rx.borrow_mut().take()
+    }
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
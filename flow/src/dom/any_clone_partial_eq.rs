@@ -2,6 +2,7 @@ pub(crate) trait AnyClonePartialEq {
     fn clone_box(&self) -> Box<dyn AnyClonePartialEq>;
     fn equals(&self, other: &dyn AnyClonePartialEq) -> bool;
     fn as_any(&self) -> &dyn std::any::Any;
+    fn into_any(self: Box<Self>) -> Box<dyn std::any::Any>;
 }
 
 impl<T: 'static + std::any::Any + Clone + PartialEq> AnyClonePartialEq for T {
@@ -12,10 +13,14 @@ impl<T: 'static + std::any::Any + Clone + PartialEq> AnyClonePartialEq for T {
         other
             .as_any()
             .downcast_ref::<T>()
-            .map_or(false, |a| self == a)
+            .is_some_and(|a| self == a)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn into_any(self: Box<Self>) -> Box<dyn std::any::Any> {
+        self
+    }
 }
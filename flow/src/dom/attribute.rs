@@ -0,0 +1,88 @@
+#[derive(Clone, PartialEq)]
+pub struct Attribute {
+    name: String,
+    value: String,
+}
+
+pub fn attribute(name: impl ToString, value: impl ToString) -> Attribute {
+    Attribute {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+pub fn id(id: impl ToString) -> Attribute {
+    attribute("id", id)
+}
+
+pub fn class(class: impl ToString) -> Attribute {
+    attribute("class", class)
+}
+
+impl Attribute {
+    /// Adds `self` to `attributes`, replacing an attribute with the same name.
+    pub(crate) fn add_to(self, attributes: &mut Vec<Attribute>) {
+        match attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == self.name)
+        {
+            Some(attribute) => attribute.value = self.value,
+            None => attributes.push(self),
+        }
+    }
+
+    /// Attributes to set, or to remove when the value is `None`, to turn `old` into `new`.
+    pub(crate) fn patch(old: &[Attribute], new: &[Attribute]) -> Vec<(String, Option<String>)> {
+        let removed = old
+            .iter()
+            .filter(|attribute| !new.iter().any(|new| new.name == attribute.name))
+            .map(|attribute| (attribute.name.clone(), None));
+        let changed = new
+            .iter()
+            .filter(|attribute| !old.contains(attribute))
+            .map(|attribute| (attribute.name.clone(), Some(attribute.value.clone())));
+
+        removed.chain(changed).collect()
+    }
+}
+
+/// State of a form control that the user can change, such as an input's text,
+/// so it is set as a DOM property instead of an attribute, which only holds the initial state.
+#[derive(Clone, PartialEq)]
+pub(crate) enum Property {
+    Value(String),
+    Checked(bool),
+}
+
+impl Property {
+    /// Adds `self` to `properties`, replacing the same property.
+    pub(crate) fn add_to(self, properties: &mut Vec<Property>) {
+        match properties
+            .iter_mut()
+            .find(|property| std::mem::discriminant(*property) == std::mem::discriminant(&self))
+        {
+            Some(property) => *property = self,
+            None => properties.push(self),
+        }
+    }
+
+    /// Properties to set to turn `old` into `new`. Removed properties are reset to their default.
+    pub(crate) fn patch(old: &[Property], new: &[Property]) -> Vec<Property> {
+        let removed = old
+            .iter()
+            .filter(|property| {
+                !new.iter()
+                    .any(|new| std::mem::discriminant(new) == std::mem::discriminant(*property))
+            })
+            .map(|property| match property {
+                Property::Value(_) => Property::Value(String::new()),
+                Property::Checked(_) => Property::Checked(false),
+            });
+        let changed = new
+            .iter()
+            .filter(|property| !old.contains(property))
+            .cloned();
+
+        removed.chain(changed).collect()
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+/// Marker types naming each element, for `ElementProps<Tag>`.
+pub mod tag {
+    macro_rules! tags {
+        ($($tag:ident),* $(,)?) => {
+            $(
+                pub struct $tag;
+            )*
+        };
+    }
+    tags!(
+        Div, Span, Ul, Ol, Li, Button, Input, Textarea, Select, OptionTag, A, Img, Form, Label, H1,
+        H2, H3, H4, H5, H6, P, Table, Thead, Tbody, Tfoot, Tr, Th, Td,
+    );
+}
+
+macro_rules! elements {
+    ($($name:ident: $tag:ident,)*) => {
+        $(
+            pub fn $name(props: impl ElementProps<tag::$tag>, children: impl IntoElement) -> Element {
+                html_element::<tag::$tag>(stringify!($name), props, children)
+            }
+        )*
+    };
+}
+
+elements!(
+    div: Div,
+    span: Span,
+    ul: Ul,
+    ol: Ol,
+    li: Li,
+    button: Button,
+    textarea: Textarea,
+    select: Select,
+    option: OptionTag,
+    a: A,
+    form: Form,
+    label: Label,
+    h1: H1,
+    h2: H2,
+    h3: H3,
+    h4: H4,
+    h5: H5,
+    h6: H6,
+    p: P,
+    table: Table,
+    thead: Thead,
+    tbody: Tbody,
+    tfoot: Tfoot,
+    tr: Tr,
+    th: Th,
+    td: Td,
+);
+
+/// `<input>` is a void element and takes no children.
+pub fn input(props: impl ElementProps<tag::Input>) -> Element {
+    html_element::<tag::Input>("input", props, ())
+}
+
+/// `<img>` is a void element and takes no children.
+pub fn img(props: impl ElementProps<tag::Img>) -> Element {
+    html_element::<tag::Img>("img", props, ())
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Href(Attribute);
+pub fn href(href: impl ToString) -> Href {
+    Href(attribute("href", href))
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Src(Attribute);
+pub fn src(src: impl ToString) -> Src {
+    Src(attribute("src", src))
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Alt(Attribute);
+pub fn alt(alt: impl ToString) -> Alt {
+    Alt(attribute("alt", alt))
+}
+
+/// The current value of `<input>`, `<textarea>` and `<select>`, set as a property,
+/// or the `value` attribute of the other elements.
+#[derive(Clone, PartialEq)]
+pub struct Value(String);
+pub fn value(value: impl ToString) -> Value {
+    Value(value.to_string())
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Placeholder(Attribute);
+pub fn placeholder(placeholder: impl ToString) -> Placeholder {
+    Placeholder(attribute("placeholder", placeholder))
+}
+
+#[derive(Clone, PartialEq)]
+pub struct For(Attribute);
+/// The `for` attribute of `<label>`.
+pub fn html_for(id: impl ToString) -> For {
+    For(attribute("for", id))
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ColSpan(Attribute);
+pub fn col_span(col_span: usize) -> ColSpan {
+    ColSpan(attribute("colspan", col_span))
+}
+
+#[derive(Clone, PartialEq)]
+pub struct RowSpan(Attribute);
+pub fn row_span(row_span: usize) -> RowSpan {
+    RowSpan(attribute("rowspan", row_span))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum InputType {
+    Text,
+    Password,
+    Email,
+    Number,
+    Checkbox,
+    Radio,
+    Submit,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Type(Attribute);
+pub fn input_type(input_type: InputType) -> Type {
+    Type(attribute(
+        "type",
+        match input_type {
+            InputType::Text => "text",
+            InputType::Password => "password",
+            InputType::Email => "email",
+            InputType::Number => "number",
+            InputType::Checkbox => "checkbox",
+            InputType::Radio => "radio",
+            InputType::Submit => "submit",
+        },
+    ))
+}
+
+/// Whether an `<input>` is checked, set as a property.
+#[derive(Clone, PartialEq)]
+pub struct Checked(bool);
+pub fn checked(checked: bool) -> Checked {
+    Checked(checked)
+}
+
+/// Boolean attributes are present only when `true`.
+#[derive(Clone, PartialEq)]
+pub struct Disabled(Option<Attribute>);
+pub fn disabled(disabled: bool) -> Disabled {
+    Disabled(disabled.then(|| attribute("disabled", "")))
+}
+
+attribute_props!(
+    Href for A;
+    Src for Img;
+    Alt for Img;
+    Placeholder for Input, Textarea;
+    For for Label;
+    ColSpan for Td, Th;
+    RowSpan for Td, Th;
+    Type for Input, Button;
+    Disabled for Button, Input, Textarea, Select, OptionTag;
+);
+
+impl ElementProps<tag::Input> for Checked {
+    fn add_to(self, element: &mut HtmlElementView) {
+        ElementProps::<tag::Input>::add_to(Property::Checked(self.0), element);
+    }
+}
+
+macro_rules! value_props {
+    (property: $($property_tag:ident),+; attribute: $($attribute_tag:ident),+;) => {
+        $(
+            impl ElementProps<tag::$property_tag> for Value {
+                fn add_to(self, element: &mut HtmlElementView) {
+                    ElementProps::<tag::$property_tag>::add_to(Property::Value(self.0), element);
+                }
+            }
+        )+
+        $(
+            impl ElementProps<tag::$attribute_tag> for Value {
+                fn add_to(self, element: &mut HtmlElementView) {
+                    ElementProps::<tag::$attribute_tag>::add_to(attribute("value", self.0), element);
+                }
+            }
+        )+
+    };
+}
+value_props!(
+    property: Input, Textarea, Select;
+    attribute: OptionTag, Button, Li;
+);
@@ -0,0 +1,1112 @@
+//! In-memory DOM backend, for running views and reducers under plain `cargo test`.
+
+use crate::{
+    render_tree::{Node, OnMount, TreeContext},
+    start::Runtime,
+    Attribute, Dispatcher, HtmlElementView, HtmlStyle, Property, Render, Shutdown, Subscription,
+    TextView, Update,
+};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::Write,
+    rc::{Rc, Weak},
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Mounts a view like `start_dom` does, but into a `HeadlessNode` tree instead of the browser DOM.
+///
+/// Events are queued by synthetic events such as `HeadlessNode::click`
+/// and processed by `flush`.
+pub struct HeadlessApp<Model, ToView> {
+    runtime: Runtime<Model, ToView>,
+    on_mount: Box<OnMount<'static>>,
+    dispatcher: Dispatcher,
+    rx: UnboundedReceiver<Box<dyn Any>>,
+    root: HeadlessNode,
+}
+
+impl<Model, View: Render + PartialEq + Clone + 'static, ToView: Fn(&Model) -> View>
+    HeadlessApp<Model, ToView>
+{
+    pub fn new(model: Model, to_view: ToView) -> Self {
+        Self::with_subscriptions(model, to_view, |_| vec![])
+    }
+
+    pub fn with_subscriptions(
+        model: Model,
+        to_view: ToView,
+        subscriptions: impl Fn(&Model) -> Vec<Subscription> + 'static,
+    ) -> Self {
+        let root = HeadlessNode::element("div");
+        let dispatcher = Dispatcher::new();
+        let rx = dispatcher.take_receiver().unwrap();
+
+        let mut app = Self {
+            runtime: Runtime::new(model, to_view, Box::new(subscriptions)),
+            on_mount: Box::new(on_mount(root.clone())),
+            dispatcher,
+            rx,
+            root,
+        };
+        let context = tree_context(app.on_mount.as_ref(), &app.dispatcher);
+        app.runtime.update_view(&context);
+        app
+    }
+
+    pub fn model(&self) -> &Model {
+        self.runtime.model()
+    }
+
+    /// The element the view is mounted into.
+    pub fn root(&self) -> &HeadlessNode {
+        &self.root
+    }
+
+    /// HTML of everything mounted under the root.
+    pub fn html(&self) -> String {
+        self.root.inner_html()
+    }
+
+    /// Queues `event` as if a view had dispatched it.
+    pub fn dispatch(&self, event: impl Any) {
+        self.dispatcher.dispatch(event);
+    }
+
+    /// A handle that queues events like `dispatch`, for code outside the view.
+    pub fn dispatcher(&self) -> Dispatcher {
+        self.dispatcher.clone()
+    }
+
+    /// Reduces every event dispatched so far, then updates the view once if any of them changed
+    /// the model. Effects are polled without blocking, and the events of the ones that resolve
+    /// are reduced as well.
+    pub fn flush<Kind>(&mut self)
+    where
+        Model: Update<Kind>,
+    {
+        let context = tree_context(self.on_mount.as_ref(), &self.dispatcher);
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let mut changed = false;
+        loop {
+            while let Ok(event) = self.rx.try_recv() {
+                if event.is::<Shutdown>() {
+                    self.runtime.shutdown(&context);
+                    self.rx.close();
+                    return;
+                }
+                changed |= self.runtime.reduce(event, &context);
+            }
+            if !self.runtime.poll_effects(&mut cx, &self.dispatcher) {
+                break;
+            }
+        }
+        if changed {
+            self.runtime.update_view(&context);
+        }
+    }
+
+    /// Keys of the running subscriptions, in the order the model listed them.
+    pub fn subscriptions(&self) -> Vec<&str> {
+        self.runtime.subscription_keys()
+    }
+
+    /// Number of effects still waiting on their future, to be polled again by the next `flush`.
+    pub fn pending_effects(&self) -> usize {
+        self.runtime.pending_effects()
+    }
+}
+
+fn tree_context<'a>(on_mount: &'a OnMount<'static>, dispatcher: &Dispatcher) -> TreeContext<'a> {
+    TreeContext {
+        on_mount,
+        on_update: &on_update,
+        on_unmount: &on_unmount,
+        on_move: &on_move,
+        dispatcher: dispatcher.clone(),
+    }
+}
+
+fn on_mount(root: HeadlessNode) -> impl Fn(&Node, &Vec<&Node>, Option<&Node>) {
+    move |node: &Node, ancestors: &Vec<&Node>, next_sibling: Option<&Node>| {
+        if let Some(view) = node.box_render.as_any().downcast_ref::<HtmlElementView>() {
+            let element = HeadlessNode::element(view.tag());
+            element.apply_attribute_patch(Attribute::patch(&[], view.attributes()));
+            element.apply_property_patch(Property::patch(&[], view.properties()));
+            element.apply_style_patch(HtmlStyle::patch(None, view.style()));
+            element.inner.borrow_mut().on_click =
+                Some(Rc::new(super::bubbling_click_listener(node, ancestors)));
+
+            let parent = find_headless_parent(ancestors).unwrap_or_else(|| root.clone());
+            parent.insert_before(&element, next_sibling.and_then(platform_node).as_ref());
+
+            *node.platform_data.borrow_mut() = Some(Box::new(element));
+        } else if let Some(text_view) = node.box_render.as_any().downcast_ref::<TextView>() {
+            let text_node = HeadlessNode::text(text_view.text());
+
+            let parent = find_headless_parent(ancestors).unwrap_or_else(|| root.clone());
+            parent.insert_before(&text_node, next_sibling.and_then(platform_node).as_ref());
+
+            *node.platform_data.borrow_mut() = Some(Box::new(text_node));
+        }
+    }
+}
+
+fn on_update(old: &dyn Render, new: &dyn Render, node: &Node) {
+    if let (Some(old), Some(new)) = (
+        old.as_any().downcast_ref::<HtmlElementView>(),
+        new.as_any().downcast_ref::<HtmlElementView>(),
+    ) {
+        let element = platform_node(node).unwrap();
+        element.apply_attribute_patch(Attribute::patch(old.attributes(), new.attributes()));
+        element.apply_property_patch(Property::patch(old.properties(), new.properties()));
+        element.apply_style_patch(HtmlStyle::patch(old.style(), new.style()));
+    } else if let (Some(old), Some(new)) = (
+        old.as_any().downcast_ref::<TextView>(),
+        new.as_any().downcast_ref::<TextView>(),
+    ) {
+        if old.text() != new.text() {
+            platform_node(node).unwrap().inner.borrow_mut().text = Some(new.text().to_string());
+        }
+    }
+}
+
+fn on_unmount(node: &Node) {
+    if let Some(platform_data) = node.platform_data.borrow_mut().take() {
+        let headless_node = platform_data.downcast_ref::<HeadlessNode>().unwrap();
+        headless_node.inner.borrow_mut().on_click = None;
+        headless_node.remove();
+    }
+}
+
+fn on_move(node: &Node, _ancestors: &Vec<&Node>, next_sibling: Option<&Node>) {
+    if let Some(headless_node) = platform_node(node) {
+        let parent = headless_node.parent().unwrap();
+        parent.insert_before(
+            &headless_node,
+            next_sibling.and_then(platform_node).as_ref(),
+        );
+    }
+}
+
+fn platform_node(node: &Node) -> Option<HeadlessNode> {
+    node.platform_data.borrow().as_ref().map(|platform_data| {
+        platform_data
+            .downcast_ref::<HeadlessNode>()
+            .unwrap()
+            .clone()
+    })
+}
+
+fn find_headless_parent(ancestors: &[&Node]) -> Option<HeadlessNode> {
+    ancestors.iter().rev().find_map(|ancestor| {
+        ancestor
+            .platform_data
+            .borrow()
+            .as_ref()
+            .map(|platform_data| {
+                platform_data
+                    .downcast_ref::<HeadlessNode>()
+                    .unwrap()
+                    .clone()
+            })
+    })
+}
+
+/// A node of the in-memory DOM. Cloning it clones the handle, not the node.
+#[derive(Clone)]
+pub struct HeadlessNode {
+    inner: Rc<RefCell<HeadlessNodeInner>>,
+}
+
+struct HeadlessNodeInner {
+    tag: String,
+    text: Option<String>,
+    attributes: BTreeMap<String, String>,
+    style: Vec<(String, String)>,
+    value: String,
+    checked: bool,
+    children: Vec<HeadlessNode>,
+    parent: Weak<RefCell<HeadlessNodeInner>>,
+    on_click: Option<Rc<dyn Fn()>>,
+}
+
+impl HeadlessNode {
+    pub(crate) fn element(tag: impl ToString) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(HeadlessNodeInner {
+                tag: tag.to_string(),
+                text: None,
+                attributes: BTreeMap::new(),
+                style: vec![],
+                value: String::new(),
+                checked: false,
+                children: vec![],
+                parent: Weak::new(),
+                on_click: None,
+            })),
+        }
+    }
+
+    pub(crate) fn text(text: impl ToString) -> Self {
+        let node = Self::element("#text");
+        node.inner.borrow_mut().text = Some(text.to_string());
+        node
+    }
+
+    /// Inserts `child` before `next_sibling`, or at the end without one,
+    /// detaching it from its current parent first.
+    pub(crate) fn insert_before(&self, child: &HeadlessNode, next_sibling: Option<&HeadlessNode>) {
+        child.remove();
+        child.inner.borrow_mut().parent = Rc::downgrade(&self.inner);
+
+        let mut inner = self.inner.borrow_mut();
+        let index = next_sibling
+            .and_then(|next_sibling| {
+                inner
+                    .children
+                    .iter()
+                    .position(|child| Rc::ptr_eq(&child.inner, &next_sibling.inner))
+            })
+            .unwrap_or(inner.children.len());
+        inner.children.insert(index, child.clone());
+    }
+
+    pub(crate) fn apply_attribute_patch(&self, patch: Vec<(String, Option<String>)>) {
+        let attributes = &mut self.inner.borrow_mut().attributes;
+        for (name, value) in patch {
+            match value {
+                Some(value) => attributes.insert(name, value),
+                None => attributes.remove(&name),
+            };
+        }
+    }
+
+    pub(crate) fn apply_property_patch(&self, patch: Vec<Property>) {
+        let mut inner = self.inner.borrow_mut();
+        for property in patch {
+            match property {
+                Property::Value(value) => inner.value = value,
+                Property::Checked(checked) => inner.checked = checked,
+            }
+        }
+    }
+
+    pub(crate) fn apply_style_patch(&self, patch: Vec<(&'static str, Option<String>)>) {
+        let style = &mut self.inner.borrow_mut().style;
+        for (name, value) in patch {
+            let index = style.iter().position(|(property, _)| property == name);
+            match (index, value) {
+                (Some(index), Some(value)) => style[index].1 = value,
+                (None, Some(value)) => style.push((name.to_string(), value)),
+                (Some(index), None) => {
+                    style.remove(index);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    pub(crate) fn remove(&self) {
+        if let Some(parent) = self.parent() {
+            parent
+                .inner
+                .borrow_mut()
+                .children
+                .retain(|child| !Rc::ptr_eq(&child.inner, &self.inner));
+        }
+        self.inner.borrow_mut().parent = Weak::new();
+    }
+
+    /// Tag name, or `#text` for text nodes.
+    pub fn tag(&self) -> String {
+        self.inner.borrow().tag.clone()
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        self.inner.borrow().attributes.get(name).cloned()
+    }
+
+    /// The `value` property of a form control, which is not reflected in its attributes.
+    pub fn value(&self) -> String {
+        self.inner.borrow().value.clone()
+    }
+
+    /// The `checked` property of an input, which is not reflected in its attributes.
+    pub fn checked(&self) -> bool {
+        self.inner.borrow().checked
+    }
+
+    /// Value of an inline style property, by its CSS name.
+    pub fn style(&self, name: &str) -> Option<String> {
+        self.inner
+            .borrow()
+            .style
+            .iter()
+            .find(|(property, _)| property == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn children(&self) -> Vec<HeadlessNode> {
+        self.inner.borrow().children.clone()
+    }
+
+    pub fn parent(&self) -> Option<HeadlessNode> {
+        self.inner
+            .borrow()
+            .parent
+            .upgrade()
+            .map(|inner| HeadlessNode { inner })
+    }
+
+    /// Concatenated text of this node and all of its descendants.
+    pub fn text_content(&self) -> String {
+        match &self.inner.borrow().text {
+            Some(text) => text.clone(),
+            None => self
+                .children()
+                .iter()
+                .map(|child| child.text_content())
+                .collect(),
+        }
+    }
+
+    /// Descendant elements with the given tag, in document order.
+    pub fn find_all(&self, tag: &str) -> Vec<HeadlessNode> {
+        let mut found = vec![];
+        for child in self.children() {
+            if child.tag() == tag {
+                found.push(child.clone());
+            }
+            found.extend(child.find_all(tag));
+        }
+        found
+    }
+
+    /// Dispatches a synthetic click on this node and every ancestor, like a bubbling DOM click.
+    ///
+    /// Resulting events are queued until `HeadlessApp::flush`.
+    pub fn click(&self) {
+        let mut node = Some(self.clone());
+        while let Some(current) = node {
+            let on_click = current.inner.borrow().on_click.clone();
+            if let Some(on_click) = on_click {
+                on_click();
+            }
+            node = current.parent();
+        }
+    }
+
+    pub fn inner_html(&self) -> String {
+        self.children()
+            .iter()
+            .map(|child| child.to_html())
+            .collect()
+    }
+
+    pub fn to_html(&self) -> String {
+        let inner = self.inner.borrow();
+        if let Some(text) = &inner.text {
+            return escape_html(text);
+        }
+        let tag = &inner.tag;
+
+        let mut html = format!("<{tag}");
+        for (name, value) in &inner.attributes {
+            write!(html, " {name}=\"{}\"", escape_html(value)).unwrap();
+        }
+        if !inner.style.is_empty() {
+            let style = inner
+                .style
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+            write!(html, " style=\"{}\"", escape_html(&style)).unwrap();
+        }
+        html.push('>');
+        if !is_void_element(tag) {
+            write!(html, "{}</{tag}>", self.inner_html()).unwrap();
+        }
+        html
+    }
+}
+
+/// Elements that never have children and so are written without a closing tag.
+fn is_void_element(tag: &str) -> bool {
+    matches!(tag, "input" | "img")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    struct CounterModel {
+        count: usize,
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Increment;
+
+    #[derive(Clone, PartialEq)]
+    struct SetCount(usize);
+
+    impl Reduce for CounterModel {
+        fn reduce(self, event: &dyn Any) -> Self {
+            if let Some(SetCount(count)) = event.downcast_ref::<SetCount>() {
+                return CounterModel { count: *count };
+            }
+            match event.downcast_ref::<Increment>() {
+                Some(Increment) => CounterModel {
+                    count: self.count + 1,
+                },
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct CounterView {
+        count: usize,
+    }
+
+    impl Render for CounterView {
+        fn render(&self) -> Element {
+            let style = HtmlStyle {
+                text_decoration: (self.count > 0).then_some(TextDecoration::LineThrough),
+                ..default()
+            };
+            Element::Multiple {
+                elements: Rc::new(
+                    (0..=self.count)
+                        .map(|index| {
+                            Element::keyed(index, li((style.clone(), on_click(Increment)), ()))
+                        })
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    fn counter_app() -> HeadlessApp<CounterModel, impl Fn(&CounterModel) -> CounterView> {
+        HeadlessApp::new(CounterModel { count: 0 }, |model: &CounterModel| {
+            CounterView { count: model.count }
+        })
+    }
+
+    #[test]
+    fn serializes_mounted_view() {
+        let app = counter_app();
+
+        assert_eq!(app.html(), "<li></li>");
+    }
+
+    #[test]
+    fn click_reduces_and_rerenders_on_flush() {
+        let mut app = counter_app();
+
+        app.root().find_all("li")[0].click();
+        assert_eq!(app.model().count, 0);
+
+        app.flush();
+        assert_eq!(app.model().count, 1);
+        let lis = app.root().find_all("li");
+        assert_eq!(lis.len(), 2);
+        for li in lis {
+            assert_eq!(
+                li.style("text-decoration"),
+                Some("line-through".to_string())
+            );
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct NestedCounterView {
+        count: usize,
+    }
+
+    impl Render for NestedCounterView {
+        fn render(&self) -> Element {
+            let count = self.count;
+            div(
+                (),
+                li((), "x").event(move |builder| {
+                    builder.on_click_fn(move |_| Some(SetCount(count + 1)));
+                }),
+            )
+        }
+    }
+
+    #[test]
+    fn nested_click_handler_sees_the_latest_props() {
+        let mut app = HeadlessApp::new(CounterModel { count: 0 }, |model: &CounterModel| {
+            NestedCounterView { count: model.count }
+        });
+
+        app.root().find_all("li")[0].click();
+        app.flush();
+        assert_eq!(app.model().count, 1);
+
+        app.root().find_all("li")[0].click();
+        app.flush();
+        assert_eq!(app.model().count, 2);
+    }
+
+    #[test]
+    fn burst_of_events_renders_once() {
+        let views = Rc::new(std::cell::Cell::new(0));
+        let mut app = HeadlessApp::new(CounterModel { count: 0 }, {
+            let views = views.clone();
+            move |model: &CounterModel| {
+                views.set(views.get() + 1);
+                CounterView { count: model.count }
+            }
+        });
+
+        for _ in 0..50 {
+            app.dispatch(Increment);
+        }
+        app.flush();
+
+        assert_eq!(app.model().count, 50);
+        assert_eq!(app.root().find_all("li").len(), 51);
+        assert_eq!(views.get(), 2);
+    }
+
+    struct LabelModel {
+        label: &'static str,
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Rename(&'static str);
+
+    impl Reduce for LabelModel {
+        fn reduce(self, event: &dyn Any) -> Self {
+            match event.downcast_ref::<Rename>() {
+                Some(Rename(label)) => LabelModel { label },
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct LabelView {
+        label: &'static str,
+    }
+
+    impl Render for LabelView {
+        fn render(&self) -> Element {
+            li(on_click(Rename("b < c")), self.label)
+        }
+    }
+
+    #[test]
+    fn string_children_mount_as_text_nodes() {
+        let app = HeadlessApp::new(LabelModel { label: "b < c" }, |model: &LabelModel| {
+            LabelView { label: model.label }
+        });
+        let text_nodes = app.root().find_all("#text");
+        assert_eq!(text_nodes.len(), 1);
+        assert_eq!(text_nodes[0].text_content(), "b < c");
+        assert!(app.html().ends_with(">b &lt; c</li>"));
+    }
+
+    #[test]
+    fn text_node_is_updated_in_place() {
+        let mut app = HeadlessApp::new(LabelModel { label: "a" }, |model: &LabelModel| LabelView {
+            label: model.label,
+        });
+        assert_eq!(app.html(), "<li>a</li>");
+        let text_node = app.root().find_all("#text")[0].clone();
+
+        app.root().find_all("li")[0].click();
+        app.flush();
+
+        assert_eq!(app.html(), "<li>b &lt; c</li>");
+        assert_eq!(text_node.text_content(), "b < c");
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct BadgeView {
+        label: &'static str,
+    }
+
+    impl Render for BadgeView {
+        fn render(&self) -> Element {
+            let style = HtmlStyle {
+                text_decoration: (self.label == "a").then_some(TextDecoration::LineThrough),
+                ..default()
+            };
+            li((on_click(Rename("b < c")), class(self.label), style), ())
+        }
+    }
+
+    #[test]
+    fn props_update_patches_attributes_and_style_in_place() {
+        let mut app = HeadlessApp::new(LabelModel { label: "a" }, |model: &LabelModel| BadgeView {
+            label: model.label,
+        });
+        let li = app.root().find_all("li")[0].clone();
+        assert_eq!(li.attribute("class"), Some("a".to_string()));
+        assert_eq!(
+            li.style("text-decoration"),
+            Some("line-through".to_string())
+        );
+
+        li.click();
+        app.flush();
+
+        assert_eq!(app.html(), "<li class=\"b &lt; c\"></li>");
+        assert_eq!(li.attribute("class"), Some("b < c".to_string()));
+        assert_eq!(li.style("text-decoration"), None);
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct FormView {
+        label: &'static str,
+    }
+
+    impl Render for FormView {
+        fn render(&self) -> Element {
+            form(
+                id("form"),
+                (
+                    label(html_for("name"), self.label),
+                    input((
+                        id("name"),
+                        input_type(InputType::Text),
+                        placeholder("Name"),
+                        checked(false),
+                        disabled(true),
+                        (self.label == "b").then(|| class("renamed")),
+                    )),
+                    a(href("/help"), "help"),
+                    " or ",
+                    (),
+                    vec![ItemView { item: 1 }],
+                    (self.label == "b").then_some("renamed"),
+                    fragment((2..4).map(|item| ItemView { item })),
+                ),
+            )
+        }
+    }
+
+    #[test]
+    fn mounts_each_element_with_its_tag_and_typed_props() {
+        let mut app = HeadlessApp::new(LabelModel { label: "a" }, |model: &LabelModel| FormView {
+            label: model.label,
+        });
+        assert_eq!(
+            app.html(),
+            "<form id=\"form\"><label for=\"name\">a</label>\
+             <input disabled=\"\" id=\"name\" placeholder=\"Name\" type=\"text\">\
+             <a href=\"/help\">help</a> or <li>1</li><li>2</li><li>3</li></form>"
+        );
+
+        app.dispatch(Rename("b"));
+        app.flush();
+
+        assert_eq!(app.root().find_all("label")[0].text_content(), "b");
+        assert!(app
+            .html()
+            .ends_with("<li>1</li>renamed<li>2</li><li>3</li></form>"));
+        assert_eq!(
+            app.root().find_all("input")[0].attribute("class"),
+            Some("renamed".to_string())
+        );
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ControlsView {
+        label: &'static str,
+    }
+
+    impl Render for ControlsView {
+        fn render(&self) -> Element {
+            (
+                input((value(self.label), checked(self.label == "b"))),
+                textarea(value(self.label), ()),
+                img(src("x.png")),
+            )
+                .into_element()
+        }
+    }
+
+    #[test]
+    fn value_and_checked_are_set_as_properties() {
+        let mut app = HeadlessApp::new(LabelModel { label: "a" }, |model: &LabelModel| {
+            ControlsView { label: model.label }
+        });
+        assert_eq!(
+            app.html(),
+            "<input><textarea></textarea><img src=\"x.png\">"
+        );
+        let input = app.root().find_all("input")[0].clone();
+        let textarea = app.root().find_all("textarea")[0].clone();
+        assert_eq!(input.value(), "a");
+        assert!(!input.checked());
+        assert_eq!(textarea.value(), "a");
+
+        app.dispatch(Rename("b"));
+        app.flush();
+
+        assert_eq!(
+            app.html(),
+            "<input><textarea></textarea><img src=\"x.png\">"
+        );
+        assert_eq!(input.value(), "b");
+        assert!(input.checked());
+        assert_eq!(textarea.value(), "b");
+    }
+
+    #[derive(Reduce)]
+    #[reduce(events(RemoveItem, SetItems))]
+    struct ListModel {
+        #[reduce(skip)]
+        items: Vec<usize>,
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct RemoveItem(usize);
+
+    struct SetItems(Vec<usize>);
+
+    impl ReduceEvent<RemoveItem> for ListModel {
+        fn reduce_event(mut self, RemoveItem(item): &RemoveItem) -> Self {
+            self.items.retain(|existing| existing != item);
+            self
+        }
+    }
+
+    impl ReduceEvent<SetItems> for ListModel {
+        fn reduce_event(self, SetItems(items): &SetItems) -> Self {
+            ListModel {
+                items: items.clone(),
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ListView {
+        items: Vec<usize>,
+    }
+
+    impl Render for ListView {
+        fn render(&self) -> Element {
+            Element::Multiple {
+                elements: Rc::new(
+                    self.items
+                        .iter()
+                        .copied()
+                        .map(|item| {
+                            Element::keyed(item, li(on_click(RemoveItem(item)), item.to_string()))
+                        })
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn unmount_removes_nodes() {
+        let mut app = HeadlessApp::new(
+            ListModel {
+                items: vec![1, 2, 3],
+            },
+            |model: &ListModel| ListView {
+                items: model.items.clone(),
+            },
+        );
+        let removed = app.root().find_all("li")[1].clone();
+
+        removed.click();
+        app.flush();
+
+        assert_eq!(app.html(), "<li>1</li><li>3</li>");
+        assert!(removed.parent().is_none());
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ItemView {
+        item: usize,
+    }
+
+    impl Render for ItemView {
+        fn render(&self) -> Element {
+            li((), self.item.to_string())
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ItemListView {
+        items: Vec<usize>,
+    }
+
+    impl Render for ItemListView {
+        fn render(&self) -> Element {
+            Element::Multiple {
+                elements: Rc::new(
+                    self.items
+                        .iter()
+                        .copied()
+                        .map(|item| Element::keyed(item, ItemView { item }))
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn inserts_and_moves_keep_dom_order() {
+        let mut app = HeadlessApp::new(
+            ListModel {
+                items: vec![1, 2, 3],
+            },
+            |model: &ListModel| ItemListView {
+                items: model.items.clone(),
+            },
+        );
+        let li_3 = app.root().find_all("li")[2].clone();
+
+        app.dispatch(SetItems(vec![1, 4, 2, 3]));
+        app.flush();
+        assert_eq!(app.html(), "<li>1</li><li>4</li><li>2</li><li>3</li>");
+
+        app.dispatch(SetItems(vec![3, 1, 4]));
+        app.flush();
+        assert_eq!(app.html(), "<li>3</li><li>1</li><li>4</li>");
+        assert!(Rc::ptr_eq(&app.root().find_all("li")[0].inner, &li_3.inner));
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct RsxListView {
+        items: Vec<usize>,
+    }
+
+    impl Render for RsxListView {
+        fn render(&self) -> Element {
+            rsx! {
+                <ul class="items" data-count={self.items.len()}>
+                    for item in self.items.iter().copied() {
+                        if item == 2 {
+                            <ItemView key={item} item={item} />
+                        } else {
+                            <li key={item} on_click={RemoveItem(item)}>"#"{item.to_string()}</li>
+                        }
+                    }
+                </ul>
+                if self.items.is_empty() {
+                    <input type={InputType::Text} placeholder="Add" disabled />
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rsx_expands_to_element_constructors() {
+        let mut app = HeadlessApp::new(ListModel { items: vec![1, 2] }, |model: &ListModel| {
+            RsxListView {
+                items: model.items.clone(),
+            }
+        });
+        assert_eq!(
+            app.html(),
+            "<ul class=\"items\" data-count=\"2\"><li>#1</li><li>2</li></ul>"
+        );
+
+        app.root().find_all("li")[0].click();
+        app.flush();
+        assert_eq!(
+            app.html(),
+            "<ul class=\"items\" data-count=\"1\"><li>2</li></ul>"
+        );
+
+        app.dispatch(SetItems(vec![]));
+        app.flush();
+        assert_eq!(
+            app.html(),
+            "<ul class=\"items\" data-count=\"0\"></ul>\
+             <input disabled=\"\" placeholder=\"Add\" type=\"text\">"
+        );
+    }
+
+    thread_local! {
+        static MOUNTED_LABELS: std::cell::RefCell<Vec<usize>> = const { std::cell::RefCell::new(vec![]) };
+    }
+
+    fn item_label_mounted(label: &ItemLabel) {
+        MOUNTED_LABELS.with(|mounted| mounted.borrow_mut().push(label.item));
+    }
+
+    #[component(on_mount = item_label_mounted)]
+    fn item_label(item: usize, selected: bool) -> Element {
+        rsx! {
+            <li class={if *selected { "selected" } else { "" }}>{item.to_string()}</li>
+        }
+    }
+
+    #[test]
+    fn component_generates_props_struct_and_render() {
+        let app = HeadlessApp::new(ListModel { items: vec![1, 2] }, |model: &ListModel| {
+            ItemLabel {
+                item: model.items.len(),
+                selected: true,
+            }
+        });
+
+        assert_eq!(app.html(), "<li class=\"selected\">2</li>");
+        assert_eq!(
+            MOUNTED_LABELS.with(|mounted| mounted.borrow().clone()),
+            vec![2]
+        );
+    }
+
+    struct InPlaceListModel {
+        items: Vec<usize>,
+    }
+
+    impl UpdateInPlace for InPlaceListModel {
+        fn reduce_mut(&mut self, event: &dyn Any) -> bool {
+            let Some(RemoveItem(item)) = event.downcast_ref::<RemoveItem>() else {
+                return false;
+            };
+            let len = self.items.len();
+            self.items.retain(|existing| existing != item);
+            self.items.len() != len
+        }
+    }
+
+    #[test]
+    fn update_in_place_skips_view_when_unchanged() {
+        let views = Rc::new(std::cell::Cell::new(0));
+        let mut app = HeadlessApp::new(InPlaceListModel { items: vec![1, 2] }, {
+            let views = views.clone();
+            move |model: &InPlaceListModel| {
+                views.set(views.get() + 1);
+                ListView {
+                    items: model.items.clone(),
+                }
+            }
+        });
+        assert_eq!(views.get(), 1);
+
+        app.dispatch(RemoveItem(3));
+        app.dispatch(Increment);
+        app.flush();
+        assert_eq!(views.get(), 1);
+
+        app.dispatch(RemoveItem(1));
+        app.flush();
+        assert_eq!(views.get(), 2);
+        assert_eq!(app.html(), "<li>2</li>");
+    }
+
+    struct Ticks(Rc<RefCell<std::collections::VecDeque<usize>>>);
+
+    impl futures_core::Stream for Ticks {
+        type Item = SetItems;
+
+        fn poll_next(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<SetItems>> {
+            match self.0.borrow_mut().pop_front() {
+                Some(0) => std::task::Poll::Ready(None),
+                Some(tick) => std::task::Poll::Ready(Some(SetItems(vec![tick]))),
+                None => std::task::Poll::Pending,
+            }
+        }
+    }
+
+    #[test]
+    fn subscriptions_start_and_cancel_as_the_model_changes() {
+        type Emit = Rc<dyn Fn(RemoveItem)>;
+        let emit: Rc<RefCell<Option<Emit>>> = Rc::default();
+        let stops = Rc::new(std::cell::Cell::new(0));
+        let ticks = Rc::new(RefCell::new(std::collections::VecDeque::new()));
+
+        let mut app = HeadlessApp::with_subscriptions(
+            ListModel { items: vec![1, 2] },
+            |model: &ListModel| ListView {
+                items: model.items.clone(),
+            },
+            {
+                let (emit, stops, ticks) = (emit.clone(), stops.clone(), ticks.clone());
+                move |model: &ListModel| {
+                    let mut subscriptions =
+                        vec![Subscription::stream("ticks", Ticks(ticks.clone()))];
+                    if model.items.contains(&1) {
+                        let (emit, stops) = (emit.clone(), stops.clone());
+                        subscriptions.push(Subscription::new("remove", move |dispatch| {
+                            *emit.borrow_mut() = Some(dispatch);
+                            move || stops.set(stops.get() + 1)
+                        }));
+                    }
+                    subscriptions
+                }
+            },
+        );
+        assert_eq!(app.subscriptions(), vec!["ticks", "remove"]);
+
+        let dispatch = emit.borrow().clone().unwrap();
+        dispatch(RemoveItem(2));
+        app.flush();
+        assert_eq!(app.html(), "<li>1</li>");
+        assert_eq!(stops.get(), 0);
+
+        dispatch(RemoveItem(1));
+        app.flush();
+        assert_eq!(app.subscriptions(), vec!["ticks"]);
+        assert_eq!(stops.get(), 1);
+
+        ticks.borrow_mut().extend([3, 4]);
+        app.flush();
+        assert_eq!(app.html(), "<li>4</li>");
+
+        ticks.borrow_mut().extend([0, 5]);
+        app.flush();
+        assert_eq!(app.html(), "<li>4</li>");
+        assert_eq!(app.subscriptions(), vec!["ticks"]);
+    }
+
+    #[test]
+    fn shutdown_unmounts_the_tree_and_ends_the_loop() {
+        let stops = Rc::new(std::cell::Cell::new(0));
+        let mut app = HeadlessApp::with_subscriptions(
+            ListModel { items: vec![1, 2] },
+            |model: &ListModel| ListView {
+                items: model.items.clone(),
+            },
+            {
+                let stops = stops.clone();
+                move |_: &ListModel| {
+                    let stops = stops.clone();
+                    vec![Subscription::new(
+                        "stop",
+                        move |_: Rc<dyn Fn(RemoveItem)>| move || stops.set(stops.get() + 1),
+                    )]
+                }
+            },
+        );
+        let dispatcher = app.dispatcher();
+
+        dispatcher.dispatch(RemoveItem(1));
+        dispatcher.shutdown();
+        dispatcher.dispatch(RemoveItem(2));
+        app.flush();
+
+        assert_eq!(app.model().items, vec![2]);
+        assert_eq!(app.html(), "");
+        assert_eq!(stops.get(), 1);
+        assert!(app.subscriptions().is_empty());
+    }
+}
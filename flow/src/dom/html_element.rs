@@ -0,0 +1,213 @@
+use super::*;
+use crate::Render;
+
+/// The view of every HTML element, such as the ones built by `div` or `li`.
+#[derive(Clone, PartialEq)]
+pub struct HtmlElementView {
+    tag: &'static str,
+    style: Option<HtmlStyle>,
+    attributes: Vec<Attribute>,
+    properties: Vec<Property>,
+    on_click: Option<OnClick>,
+    children: Element,
+}
+
+impl HtmlElementView {
+    pub fn tag(&self) -> &'static str {
+        self.tag
+    }
+    pub(crate) fn style(&self) -> Option<&HtmlStyle> {
+        self.style.as_ref()
+    }
+    pub(crate) fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+    pub(crate) fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+}
+
+impl Render for HtmlElementView {
+    fn render(&self) -> Element {
+        self.children.clone()
+    }
+}
+
+pub(crate) fn html_element<Tag>(
+    tag: &'static str,
+    props: impl ElementProps<Tag>,
+    children: impl IntoElement,
+) -> Element {
+    let mut view = HtmlElementView {
+        tag,
+        style: None,
+        attributes: vec![],
+        properties: vec![],
+        on_click: None,
+        children: children.into_element(),
+    };
+    props.add_to(&mut view);
+    let on_click = view.on_click.clone();
+    let element = Element::single(Box::new(view));
+    match on_click {
+        Some(on_click) => element.event(move |builder| {
+            builder.on_click_box(move |_| Some(on_click.event.clone_box().into_any()));
+        }),
+        None => element,
+    }
+}
+
+/// Props accepted by the element with the marker type `Tag`, one of the types in `tag`.
+///
+/// Tuples of up to 12 props and `Option`s of props are props too.
+/// A prop that the element does not take is a compile error:
+///
+/// ```compile_fail
+/// use flow::prelude::*;
+///
+/// let _ = a(src("logo.png"), "home");
+/// ```
+///
+/// ```compile_fail
+/// use flow::prelude::*;
+///
+/// let _ = div((id("0"), id("1"), id("2"), id("3"), id("4"), id("5"), id("6"),
+///     id("7"), id("8"), id("9"), id("10"), id("11"), id("12")), ());
+/// ```
+pub trait ElementProps<Tag> {
+    fn add_to(self, element: &mut HtmlElementView);
+}
+
+impl<Tag> ElementProps<Tag> for () {
+    fn add_to(self, _element: &mut HtmlElementView) {}
+}
+impl<Tag, P: ElementProps<Tag>> ElementProps<Tag> for Option<P> {
+    fn add_to(self, element: &mut HtmlElementView) {
+        if let Some(props) = self {
+            props.add_to(element);
+        }
+    }
+}
+
+macro_rules! tuple_props {
+    ($($t:ident),+) => {
+        impl<Tag, $($t: ElementProps<Tag>),+> ElementProps<Tag> for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn add_to(self, element: &mut HtmlElementView) {
+                let ($($t,)+) = self;
+                $($t.add_to(element);)+
+            }
+        }
+    };
+}
+tuple_props!(T0);
+tuple_props!(T0, T1);
+tuple_props!(T0, T1, T2);
+tuple_props!(T0, T1, T2, T3);
+tuple_props!(T0, T1, T2, T3, T4);
+tuple_props!(T0, T1, T2, T3, T4, T5);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6, T7);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+tuple_props!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+impl<Tag> ElementProps<Tag> for HtmlStyle {
+    fn add_to(self, element: &mut HtmlElementView) {
+        element.style = Some(self);
+    }
+}
+impl<Tag> ElementProps<Tag> for Attribute {
+    fn add_to(self, element: &mut HtmlElementView) {
+        Attribute::add_to(self, &mut element.attributes);
+    }
+}
+impl<Tag> ElementProps<Tag> for Property {
+    fn add_to(self, element: &mut HtmlElementView) {
+        Property::add_to(self, &mut element.properties);
+    }
+}
+impl<Tag> ElementProps<Tag> for OnClick {
+    fn add_to(self, element: &mut HtmlElementView) {
+        element.on_click = Some(self);
+    }
+}
+
+/// Implements `ElementProps` for a prop that wraps an `Attribute` or an `Option<Attribute>`,
+/// for each listed tag.
+macro_rules! attribute_props {
+    ($($prop:ident for $($tag:ident),+;)*) => {
+        $($(
+            impl ElementProps<tag::$tag> for $prop {
+                fn add_to(self, element: &mut HtmlElementView) {
+                    if let Some(attribute) = Option::<Attribute>::from(self.0) {
+                        ElementProps::<tag::$tag>::add_to(attribute, element);
+                    }
+                }
+            }
+        )+)*
+    };
+}
+pub(crate) use attribute_props;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(element: &Element) -> &HtmlElementView {
+        match element {
+            Element::Single { box_render, .. } => box_render.as_any().downcast_ref().unwrap(),
+            _ => panic!("expected a single element"),
+        }
+    }
+
+    fn has_attributes(element: &Element, names: &[&str]) -> bool {
+        let expected: Vec<_> = names.iter().map(|name| attribute(name, "")).collect();
+        view(element).attributes() == expected
+    }
+
+    #[test]
+    fn tuple_of_twelve_props_applies_each_in_order() {
+        let element = div(
+            (
+                attribute("a0", ""),
+                attribute("a1", ""),
+                attribute("a2", ""),
+                attribute("a3", ""),
+                attribute("a4", ""),
+                attribute("a5", ""),
+                attribute("a6", ""),
+                attribute("a7", ""),
+                attribute("a8", ""),
+                attribute("a9", ""),
+                attribute("a10", ""),
+                (attribute("a11", ""), HtmlStyle::default()),
+            ),
+            (),
+        );
+        assert!(has_attributes(
+            &element,
+            &["a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7", "a8", "a9", "a10", "a11"]
+        ));
+        assert!(view(&element).style().is_some());
+    }
+
+    #[test]
+    fn optional_props_apply_only_when_some() {
+        let element = button(
+            (Some(disabled(false)), None::<Attribute>, Some(class(""))),
+            (),
+        );
+        assert!(has_attributes(&element, &["class"]));
+
+        let element = button(Some(disabled(true)), ());
+        assert!(has_attributes(&element, &["disabled"]));
+    }
+
+    #[test]
+    fn later_props_replace_earlier_ones_with_the_same_name() {
+        let element = span((class("a"), class("b")), ());
+        assert!(view(&element).attributes() == [attribute("class", "b")]);
+    }
+}
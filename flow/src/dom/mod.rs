@@ -1,14 +1,21 @@
 mod any_clone_partial_eq;
-pub mod li;
+pub mod attribute;
+pub mod elements;
+pub mod headless;
+pub mod html_element;
 mod start;
 pub mod style;
+pub mod subscriptions;
+pub mod text;
 
-use crate::{render, Element, IntoElement};
+use crate::{render, render_tree::Node, ClickEvent, Element, IntoElement};
 use any_clone_partial_eq::*;
-pub use li::*;
+pub use attribute::*;
+pub use elements::*;
+pub use html_element::*;
 pub use start::*;
 pub use style::*;
-use web_sys::HtmlElement;
+pub use text::*;
 
 impl IntoElement for &str {
     fn into_element(self) -> Element {
@@ -26,11 +33,6 @@ impl IntoElement for String {
     }
 }
 
-fn text(text: impl ToString) -> Element {
-    println!("text: {:?}", text.to_string());
-    render(())
-}
-
 pub struct OnClick {
     event: Box<dyn AnyClonePartialEq>,
 }
@@ -53,3 +55,26 @@ pub fn on_click(event: impl std::any::Any + Clone + PartialEq) -> OnClick {
         event: Box::new(event),
     }
 }
+
+/// Click listener for the platform element of `node`.
+///
+/// Component ancestors between `node` and its platform parent have no element of their own,
+/// so their handlers run here as well.
+pub(crate) fn bubbling_click_listener(node: &Node, ancestors: &[&Node]) -> impl Fn() + 'static {
+    let listeners = std::iter::once(node)
+        .chain(
+            ancestors
+                .iter()
+                .rev()
+                .copied()
+                .take_while(|ancestor| ancestor.platform_data.borrow().is_none()),
+        )
+        .map(|node| node.click_listener())
+        .collect::<Vec<_>>();
+
+    move || {
+        for listener in &listeners {
+            listener(ClickEvent {});
+        }
+    }
+}
@@ -1,10 +1,52 @@
-use crate::{render_tree::Node, LiView, Reduce, Render};
-use wasm_bindgen::JsCast;
+use crate::{
+    render_tree::Node, Attribute, Dispatcher, HtmlElementView, HtmlStyle, Platform, Property,
+    Render, Subscription, TextView, Update,
+};
+use wasm_bindgen::{closure::Closure, JsCast};
 
-pub async fn start_dom<Model: Reduce, View: Render + PartialEq + Clone + 'static>(
+struct DomNode {
+    element: web_sys::Element,
+    on_click: Closure<dyn FnMut()>,
+}
+
+/// When `start_dom` updates the DOM after the model changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// As soon as every queued event has been reduced.
+    #[default]
+    Immediate,
+    /// In a microtask, so events dispatched later in the same task render together.
+    Microtask,
+    /// On the next `requestAnimationFrame`, at most once per frame.
+    AnimationFrame,
+}
+
+async fn wait_for_flush(flush_policy: FlushPolicy) {
+    if flush_policy == FlushPolicy::Immediate {
+        return;
+    }
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let callback = Closure::once_into_js(move || {
+        let _ = tx.send(());
+    });
+    let window = web_sys::window().unwrap();
+    if flush_policy == FlushPolicy::Microtask {
+        window.queue_microtask(callback.unchecked_ref());
+    } else {
+        window
+            .request_animation_frame(callback.unchecked_ref())
+            .unwrap();
+    }
+    let _ = rx.await;
+}
+
+pub async fn start_dom<Model: Update<Kind>, Kind, View: Render + PartialEq + Clone + 'static>(
     root_id: impl ToString,
-    mut model: Model,
+    dispatcher: Dispatcher,
+    model: Model,
     to_view: impl Fn(&Model) -> View,
+    subscriptions: impl Fn(&Model) -> Vec<Subscription> + 'static,
+    flush_policy: FlushPolicy,
 ) {
     let root_id = root_id.to_string();
     let root = web_sys::window()
@@ -12,41 +54,193 @@ pub async fn start_dom<Model: Reduce, View: Render + PartialEq + Clone + 'static
         .document()
         .unwrap()
         .get_element_by_id(&root_id)
-        .expect(format!("Could not find element with id: {}", root_id).as_str());
-
-    let on_mount = |node: &Node, ancestors: &Vec<&Node>| {
-        crate::log!("on_mount");
+        .unwrap_or_else(|| panic!("Could not find element with id: {}", root_id));
 
-        if let Some(_) = node.box_render.as_any().downcast_ref::<LiView>() {
-            crate::log!("LiView::on_mount");
-            let li_element = web_sys::window()
+    let on_mount = |node: &Node, ancestors: &Vec<&Node>, next_sibling: Option<&Node>| {
+        if let Some(view) = node.box_render.as_any().downcast_ref::<HtmlElementView>() {
+            let element = web_sys::window()
                 .unwrap()
                 .document()
                 .unwrap()
-                .create_element("li")
-                .unwrap()
-                .dyn_into::<web_sys::HtmlLiElement>()
+                .create_element(view.tag())
+                .unwrap();
+
+            apply_attribute_patch(&element, Attribute::patch(&[], view.attributes()));
+            apply_property_patch(&element, Property::patch(&[], view.properties()));
+            apply_style_patch(
+                element.unchecked_ref(),
+                HtmlStyle::patch(None, view.style()),
+            );
+
+            let parent = find_dom_parent(ancestors).unwrap_or_else(|| root.clone());
+            parent
+                .insert_before(&element, next_sibling.and_then(dom_node_of).as_ref())
+                .unwrap();
+
+            let on_click =
+                Closure::<dyn FnMut()>::new(super::bubbling_click_listener(node, ancestors));
+            element
+                .add_event_listener_with_callback("click", on_click.as_ref().unchecked_ref())
                 .unwrap();
 
-            // TODO: Set style
+            *node.platform_data.borrow_mut() = Some(Box::new(DomNode { element, on_click }));
+        } else if let Some(text_view) = node.box_render.as_any().downcast_ref::<TextView>() {
+            let text_node = web_sys::window()
+                .unwrap()
+                .document()
+                .unwrap()
+                .create_text_node(text_view.text());
 
-            let parent = find_dom_parent(&ancestors).unwrap_or_else(|| root.clone());
-            parent.append_child(&li_element).unwrap();
+            let parent = find_dom_parent(ancestors).unwrap_or_else(|| root.clone());
+            parent
+                .insert_before(&text_node, next_sibling.and_then(dom_node_of).as_ref())
+                .unwrap();
 
-            *node.platform_data.lock().unwrap() = Some(Box::new(li_element));
+            *node.platform_data.borrow_mut() = Some(Box::new(text_node));
         }
     };
-    crate::start(model, to_view, &on_mount).await;
+    let on_update = |old: &dyn Render, new: &dyn Render, node: &Node| {
+        if let (Some(old), Some(new)) = (
+            old.as_any().downcast_ref::<HtmlElementView>(),
+            new.as_any().downcast_ref::<HtmlElementView>(),
+        ) {
+            let platform_data = node.platform_data.borrow();
+            let dom_node = platform_data
+                .as_ref()
+                .and_then(|platform_data| platform_data.downcast_ref::<DomNode>())
+                .unwrap();
+            apply_attribute_patch(
+                &dom_node.element,
+                Attribute::patch(old.attributes(), new.attributes()),
+            );
+            apply_property_patch(
+                &dom_node.element,
+                Property::patch(old.properties(), new.properties()),
+            );
+            apply_style_patch(
+                dom_node.element.unchecked_ref(),
+                HtmlStyle::patch(old.style(), new.style()),
+            );
+            // The click listener reads the node's event handlers on every click,
+            // and the render tree has already replaced them, so it needs no patch.
+        } else if let (Some(old), Some(new)) = (
+            old.as_any().downcast_ref::<TextView>(),
+            new.as_any().downcast_ref::<TextView>(),
+        ) {
+            if old.text() != new.text() {
+                let platform_data = node.platform_data.borrow();
+                let text_node = platform_data
+                    .as_ref()
+                    .and_then(|platform_data| platform_data.downcast_ref::<web_sys::Text>())
+                    .unwrap();
+                text_node.set_data(new.text());
+            }
+        }
+    };
+    let on_unmount = |node: &Node| {
+        let Some(platform_data) = node.platform_data.borrow_mut().take() else {
+            return;
+        };
+        if let Some(dom_node) = platform_data.downcast_ref::<DomNode>() {
+            dom_node
+                .element
+                .remove_event_listener_with_callback(
+                    "click",
+                    dom_node.on_click.as_ref().unchecked_ref(),
+                )
+                .unwrap();
+            dom_node.element.remove();
+        } else if let Some(text_node) = platform_data.downcast_ref::<web_sys::Text>() {
+            text_node.remove();
+        }
+    };
+    let on_move = |node: &Node, ancestors: &Vec<&Node>, next_sibling: Option<&Node>| {
+        if let Some(dom_node) = dom_node_of(node) {
+            let parent = find_dom_parent(ancestors).unwrap_or_else(|| root.clone());
+            parent
+                .insert_before(&dom_node, next_sibling.and_then(dom_node_of).as_ref())
+                .unwrap();
+        }
+    };
+    crate::start(
+        dispatcher,
+        model,
+        to_view,
+        subscriptions,
+        Platform {
+            on_mount: &on_mount,
+            on_update: &on_update,
+            on_unmount: &on_unmount,
+            on_move: &on_move,
+            wait_for_flush: move || wait_for_flush(flush_policy),
+        },
+    )
+    .await;
+}
+
+fn apply_attribute_patch(element: &web_sys::Element, patch: Vec<(String, Option<String>)>) {
+    for (name, value) in patch {
+        match value {
+            Some(value) => element.set_attribute(&name, &value).unwrap(),
+            None => element.remove_attribute(&name).unwrap(),
+        }
+    }
+}
+
+fn apply_property_patch(element: &web_sys::Element, patch: Vec<Property>) {
+    for property in patch {
+        match property {
+            Property::Value(value) => {
+                if let Some(input) = element.dyn_ref::<web_sys::HtmlInputElement>() {
+                    input.set_value(&value);
+                } else if let Some(textarea) = element.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+                    textarea.set_value(&value);
+                } else if let Some(select) = element.dyn_ref::<web_sys::HtmlSelectElement>() {
+                    select.set_value(&value);
+                }
+            }
+            Property::Checked(checked) => {
+                if let Some(input) = element.dyn_ref::<web_sys::HtmlInputElement>() {
+                    input.set_checked(checked);
+                }
+            }
+        }
+    }
+}
+
+fn apply_style_patch(element: &web_sys::HtmlElement, patch: Vec<(&'static str, Option<String>)>) {
+    let style = element.style();
+    for (name, value) in patch {
+        match value {
+            Some(value) => style.set_property(name, &value).unwrap(),
+            None => {
+                style.remove_property(name).unwrap();
+            }
+        }
+    }
+}
+
+fn dom_node_of(node: &Node) -> Option<web_sys::Node> {
+    let platform_data = node.platform_data.borrow();
+    let platform_data = platform_data.as_ref()?;
+    if let Some(dom_node) = platform_data.downcast_ref::<DomNode>() {
+        Some(dom_node.element.clone().into())
+    } else {
+        platform_data
+            .downcast_ref::<web_sys::Text>()
+            .map(|text_node| text_node.clone().into())
+    }
 }
 
 fn find_dom_parent(ancestors: &[&Node]) -> Option<web_sys::Element> {
     for near_ancestor in ancestors.iter().rev() {
-        let platform_data = near_ancestor.platform_data.lock().unwrap();
+        let platform_data = near_ancestor.platform_data.borrow();
         if let Some(platform_data) = platform_data.as_ref() {
             return Some(
                 platform_data
-                    .downcast_ref::<web_sys::Element>()
+                    .downcast_ref::<DomNode>()
                     .unwrap()
+                    .element
                     .clone(),
             );
         }
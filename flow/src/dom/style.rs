@@ -1,9 +1,339 @@
 #[derive(Default, Clone, PartialEq)]
 pub struct HtmlStyle {
+    pub display: Option<Display>,
+    pub color: Option<Color>,
+    pub background_color: Option<Color>,
+    pub margin: Option<Edges>,
+    pub padding: Option<Edges>,
+    pub font_family: Option<String>,
+    pub font_size: Option<Length>,
+    pub font_weight: Option<FontWeight>,
     pub text_decoration: Option<TextDecoration>,
+    pub flex_direction: Option<FlexDirection>,
+    pub flex_wrap: Option<FlexWrap>,
+    pub justify_content: Option<JustifyContent>,
+    pub align_items: Option<AlignItems>,
+    pub flex_grow: Option<f32>,
+    pub flex_shrink: Option<f32>,
+    pub flex_basis: Option<Length>,
+    pub gap: Option<Length>,
+}
+
+impl HtmlStyle {
+    pub(crate) fn css_properties(&self) -> Vec<(&'static str, String)> {
+        let mut properties = vec![];
+        let mut push = |name: &'static str, value: Option<String>| {
+            if let Some(value) = value {
+                properties.push((name, value));
+            }
+        };
+        push("display", self.display.as_ref().map(Display::to_css));
+        push("color", self.color.as_ref().map(Color::to_css));
+        push(
+            "background-color",
+            self.background_color.as_ref().map(Color::to_css),
+        );
+        push("margin", self.margin.as_ref().map(Edges::to_css));
+        push("padding", self.padding.as_ref().map(Edges::to_css));
+        push("font-family", self.font_family.clone());
+        push("font-size", self.font_size.as_ref().map(Length::to_css));
+        push(
+            "font-weight",
+            self.font_weight.as_ref().map(FontWeight::to_css),
+        );
+        push(
+            "text-decoration",
+            self.text_decoration.as_ref().map(TextDecoration::to_css),
+        );
+        push(
+            "flex-direction",
+            self.flex_direction.as_ref().map(FlexDirection::to_css),
+        );
+        push("flex-wrap", self.flex_wrap.as_ref().map(FlexWrap::to_css));
+        push(
+            "justify-content",
+            self.justify_content.as_ref().map(JustifyContent::to_css),
+        );
+        push(
+            "align-items",
+            self.align_items.as_ref().map(AlignItems::to_css),
+        );
+        push("flex-grow", self.flex_grow.map(|value| value.to_string()));
+        push(
+            "flex-shrink",
+            self.flex_shrink.map(|value| value.to_string()),
+        );
+        push("flex-basis", self.flex_basis.as_ref().map(Length::to_css));
+        push("gap", self.gap.as_ref().map(Length::to_css));
+        properties
+    }
+
+    /// CSS properties to set, or to remove when the value is `None`, to turn `old` into `new`.
+    pub(crate) fn patch(
+        old: Option<&HtmlStyle>,
+        new: Option<&HtmlStyle>,
+    ) -> Vec<(&'static str, Option<String>)> {
+        let old = old.map(HtmlStyle::css_properties).unwrap_or_default();
+        let new = new.map(HtmlStyle::css_properties).unwrap_or_default();
+
+        let removed = old
+            .iter()
+            .filter(|(name, _)| !new.iter().any(|(new_name, _)| new_name == name))
+            .map(|(name, _)| (*name, None));
+        let changed = new
+            .iter()
+            .filter(|property| !old.contains(property))
+            .map(|(name, value)| (*name, Some(value.clone())));
+
+        removed.chain(changed).collect()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Display {
+    None,
+    Block,
+    Inline,
+    InlineBlock,
+    Flex,
+    InlineFlex,
+    Grid,
+}
+
+impl Display {
+    fn to_css(&self) -> String {
+        match self {
+            Display::None => "none",
+            Display::Block => "block",
+            Display::Inline => "inline",
+            Display::InlineBlock => "inline-block",
+            Display::Flex => "flex",
+            Display::InlineFlex => "inline-flex",
+            Display::Grid => "grid",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Color {
+    Rgb(u8, u8, u8),
+    Rgba(u8, u8, u8, f32),
+    /// A CSS color keyword such as `"red"` or `"transparent"`.
+    Named(String),
+}
+
+impl Color {
+    fn to_css(&self) -> String {
+        match self {
+            Color::Rgb(r, g, b) => format!("rgb({r}, {g}, {b})"),
+            Color::Rgba(r, g, b, a) => format!("rgba({r}, {g}, {b}, {a})"),
+            Color::Named(name) => name.clone(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Length {
+    Auto,
+    Px(f32),
+    Em(f32),
+    Rem(f32),
+    Percent(f32),
+}
+
+impl Length {
+    fn to_css(&self) -> String {
+        match self {
+            Length::Auto => "auto".to_string(),
+            Length::Px(value) => format!("{value}px"),
+            Length::Em(value) => format!("{value}em"),
+            Length::Rem(value) => format!("{value}rem"),
+            Length::Percent(value) => format!("{value}%"),
+        }
+    }
+}
+
+/// Lengths for the four sides of a box, as used by `margin` and `padding`.
+#[derive(Clone, PartialEq)]
+pub struct Edges {
+    pub top: Length,
+    pub right: Length,
+    pub bottom: Length,
+    pub left: Length,
+}
+
+impl Edges {
+    pub fn all(length: Length) -> Self {
+        Self::symmetric(length.clone(), length)
+    }
+    pub fn symmetric(vertical: Length, horizontal: Length) -> Self {
+        Self {
+            top: vertical.clone(),
+            right: horizontal.clone(),
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+    fn to_css(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.top.to_css(),
+            self.right.to_css(),
+            self.bottom.to_css(),
+            self.left.to_css()
+        )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum FontWeight {
+    Normal,
+    Bold,
+    Weight(u16),
+}
+
+impl FontWeight {
+    fn to_css(&self) -> String {
+        match self {
+            FontWeight::Normal => "normal".to_string(),
+            FontWeight::Bold => "bold".to_string(),
+            FontWeight::Weight(weight) => weight.to_string(),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq)]
 pub enum TextDecoration {
+    None,
+    Underline,
+    Overline,
     LineThrough,
 }
+
+impl TextDecoration {
+    fn to_css(&self) -> String {
+        match self {
+            TextDecoration::None => "none",
+            TextDecoration::Underline => "underline",
+            TextDecoration::Overline => "overline",
+            TextDecoration::LineThrough => "line-through",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum FlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+impl FlexDirection {
+    fn to_css(&self) -> String {
+        match self {
+            FlexDirection::Row => "row",
+            FlexDirection::RowReverse => "row-reverse",
+            FlexDirection::Column => "column",
+            FlexDirection::ColumnReverse => "column-reverse",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum FlexWrap {
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+impl FlexWrap {
+    fn to_css(&self) -> String {
+        match self {
+            FlexWrap::NoWrap => "nowrap",
+            FlexWrap::Wrap => "wrap",
+            FlexWrap::WrapReverse => "wrap-reverse",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum JustifyContent {
+    FlexStart,
+    FlexEnd,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+impl JustifyContent {
+    fn to_css(&self) -> String {
+        match self {
+            JustifyContent::FlexStart => "flex-start",
+            JustifyContent::FlexEnd => "flex-end",
+            JustifyContent::Center => "center",
+            JustifyContent::SpaceBetween => "space-between",
+            JustifyContent::SpaceAround => "space-around",
+            JustifyContent::SpaceEvenly => "space-evenly",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum AlignItems {
+    FlexStart,
+    FlexEnd,
+    Center,
+    Stretch,
+    Baseline,
+}
+
+impl AlignItems {
+    fn to_css(&self) -> String {
+        match self {
+            AlignItems::FlexStart => "flex-start",
+            AlignItems::FlexEnd => "flex-end",
+            AlignItems::Center => "center",
+            AlignItems::Stretch => "stretch",
+            AlignItems::Baseline => "baseline",
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default;
+
+    #[test]
+    fn patch_sets_changed_and_removes_dropped_properties() {
+        let old = HtmlStyle {
+            color: Some(Color::Named("red".to_string())),
+            margin: Some(Edges::all(Length::Px(4.0))),
+            text_decoration: Some(TextDecoration::LineThrough),
+            ..default()
+        };
+        let new = HtmlStyle {
+            color: Some(Color::Named("red".to_string())),
+            margin: Some(Edges::symmetric(Length::Px(4.0), Length::Auto)),
+            display: Some(Display::Flex),
+            ..default()
+        };
+
+        assert_eq!(
+            HtmlStyle::patch(Some(&old), Some(&new)),
+            vec![
+                ("text-decoration", None),
+                ("display", Some("flex".to_string())),
+                ("margin", Some("4px auto 4px auto".to_string())),
+            ]
+        );
+    }
+}
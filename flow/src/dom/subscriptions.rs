@@ -0,0 +1,75 @@
+//! Browser event sources for `start_dom`'s subscriptions.
+
+use crate::Subscription;
+use std::{any::Any, time::Duration};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Dispatches `event()` every `period`.
+pub fn interval<Event: Any>(
+    key: impl ToString,
+    period: Duration,
+    event: impl Fn() -> Event + 'static,
+) -> Subscription {
+    Subscription::new(key, move |dispatch| {
+        let on_tick = Closure::<dyn FnMut()>::new(move || dispatch(event()));
+        let window = web_sys::window().unwrap();
+        let handle = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_tick.as_ref().unchecked_ref(),
+                period.as_millis() as i32,
+            )
+            .unwrap();
+        move || {
+            window.clear_interval_with_handle(handle);
+            drop(on_tick);
+        }
+    })
+}
+
+/// Dispatches `event(width, height)` with the window's inner size whenever it is resized.
+pub fn window_resize<Event: Any>(
+    key: impl ToString,
+    event: impl Fn(f64, f64) -> Event + 'static,
+) -> Subscription {
+    Subscription::new(key, move |dispatch| {
+        let window = web_sys::window().unwrap();
+        let on_resize = Closure::<dyn FnMut()>::new({
+            let window = window.clone();
+            move || {
+                let width = window.inner_width().unwrap().as_f64().unwrap();
+                let height = window.inner_height().unwrap().as_f64().unwrap();
+                dispatch(event(width, height));
+            }
+        });
+        listen(window.into(), "resize", on_resize)
+    })
+}
+
+/// Dispatches `event(key)` with the `KeyboardEvent.key` of every key pressed in the document.
+pub fn key_down<Event: Any>(
+    key: impl ToString,
+    event: impl Fn(String) -> Event + 'static,
+) -> Subscription {
+    Subscription::new(key, move |dispatch| {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let on_key_down = Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(
+            move |key: web_sys::KeyboardEvent| dispatch(event(key.key())),
+        );
+        listen(document.into(), "keydown", on_key_down)
+    })
+}
+
+fn listen<Callback: ?Sized>(
+    target: web_sys::EventTarget,
+    event_type: &'static str,
+    callback: Closure<Callback>,
+) -> impl FnOnce() {
+    target
+        .add_event_listener_with_callback(event_type, callback.as_ref().unchecked_ref())
+        .unwrap();
+    move || {
+        target
+            .remove_event_listener_with_callback(event_type, callback.as_ref().unchecked_ref())
+            .unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+use super::*;
+use crate::Render;
+
+/// A text node. `&str` and `String` children are turned into this.
+#[derive(Clone, PartialEq)]
+pub struct TextView {
+    text: String,
+}
+
+impl TextView {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Render for TextView {
+    fn render(&self) -> Element {
+        render(())
+    }
+}
+
+pub(crate) fn text(text: impl ToString) -> Element {
+    Element::single(Box::new(TextView {
+        text: text.to_string(),
+    }))
+}
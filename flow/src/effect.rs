@@ -0,0 +1,234 @@
+use crate::Dispatcher;
+use std::{
+    any::Any,
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+type EffectFuture = Pin<Box<dyn Future<Output = Box<dyn Any>>>>;
+
+/// A side effect requested by a reducer with `command`.
+///
+/// Reducers stay pure: the `start` loop runs commands after the reducer returns
+/// and dispatches the events they produce like any other event.
+pub enum Cmd {
+    /// Dispatches an event after the current one has been reduced.
+    Dispatch(Box<dyn Any>),
+    /// Runs a future on the start loop and dispatches the event it resolves to.
+    Perform(EffectFuture),
+    Batch(Vec<Cmd>),
+}
+
+impl Cmd {
+    pub fn dispatch(event: impl Any) -> Self {
+        Cmd::Dispatch(Box::new(event))
+    }
+    pub fn perform<Event: Any>(future: impl Future<Output = Event> + 'static) -> Self {
+        Cmd::Perform(Box::pin(
+            async move { Box::new(future.await) as Box<dyn Any> },
+        ))
+    }
+    pub fn batch(commands: impl IntoIterator<Item = Cmd>) -> Self {
+        Cmd::Batch(commands.into_iter().collect())
+    }
+}
+
+thread_local! {
+    static COMMANDS: RefCell<Option<Vec<Cmd>>> = const { RefCell::new(None) };
+}
+
+/// Requests `cmd` from the reducer that is handling the current event.
+pub fn command(cmd: Cmd) {
+    COMMANDS.with(|commands| match commands.borrow_mut().as_mut() {
+        Some(commands) => commands.push(cmd),
+        None => crate::error!("`command` called outside of a reducer"),
+    });
+}
+
+/// Runs `reduce` and returns the commands it requested.
+pub(crate) fn collect_commands<T>(reduce: impl FnOnce() -> T) -> (T, Vec<Cmd>) {
+    let outer = COMMANDS.with(|commands| commands.replace(Some(vec![])));
+    let output = reduce();
+    let commands = COMMANDS.with(|commands| commands.replace(outer));
+    (output, commands.unwrap_or_default())
+}
+
+/// Futures of `Cmd::Perform` that have not resolved yet.
+#[derive(Default)]
+pub(crate) struct Effects {
+    pending: Vec<EffectFuture>,
+}
+
+impl Effects {
+    pub(crate) fn run(&mut self, cmd: Cmd, dispatcher: &Dispatcher) {
+        match cmd {
+            Cmd::Dispatch(event) => dispatcher.dispatch_box(event),
+            Cmd::Perform(future) => self.pending.push(future),
+            Cmd::Batch(commands) => {
+                for cmd in commands {
+                    self.run(cmd, dispatcher);
+                }
+            }
+        }
+    }
+
+    /// Polls every pending future once and dispatches the events of the ones that resolved.
+    /// Returns whether any resolved.
+    pub(crate) fn poll(&mut self, cx: &mut Context, dispatcher: &Dispatcher) -> bool {
+        let mut resolved = false;
+        self.pending
+            .retain_mut(|future| match future.as_mut().poll(cx) {
+                Poll::Ready(event) => {
+                    dispatcher.dispatch_box(event);
+                    resolved = true;
+                    false
+                }
+                Poll::Pending => true,
+            });
+        resolved
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::headless::HeadlessApp;
+    use std::task::Waker;
+
+    #[derive(Debug, PartialEq)]
+    struct Fetched(usize);
+
+    fn received(rx: &mut crate::dispatcher::Receiver) -> Vec<usize> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|event| event.downcast::<Fetched>().unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn collect_commands_returns_the_commands_of_its_reducer_only() {
+        let (((), inner), outer) = collect_commands(|| {
+            command(Cmd::dispatch(Fetched(0)));
+            let inner = collect_commands(|| command(Cmd::dispatch(Fetched(1))));
+            command(Cmd::dispatch(Fetched(2)));
+            inner
+        });
+
+        assert_eq!(inner.len(), 1);
+        assert_eq!(outer.len(), 2);
+    }
+
+    #[test]
+    fn dispatch_and_batch_dispatch_in_order() {
+        let dispatcher = Dispatcher::new();
+        let mut rx = dispatcher.take_receiver().unwrap();
+        let mut effects = Effects::default();
+
+        effects.run(
+            Cmd::batch([
+                Cmd::dispatch(Fetched(0)),
+                Cmd::batch([Cmd::dispatch(Fetched(1))]),
+                Cmd::dispatch(Fetched(2)),
+            ]),
+            &dispatcher,
+        );
+
+        assert_eq!(received(&mut rx), [0, 1, 2]);
+        assert_eq!(effects.len(), 0);
+    }
+
+    #[test]
+    fn perform_dispatches_its_event_once_the_future_resolves() {
+        let dispatcher = Dispatcher::new();
+        let mut rx = dispatcher.take_receiver().unwrap();
+        let mut effects = Effects::default();
+        let mut cx = Context::from_waker(Waker::noop());
+        let (tx, response) = tokio::sync::oneshot::channel();
+
+        effects.run(
+            Cmd::perform(async move { Fetched(response.await.unwrap()) }),
+            &dispatcher,
+        );
+        assert!(!effects.poll(&mut cx, &dispatcher));
+        assert_eq!(effects.len(), 1);
+        assert_eq!(received(&mut rx), []);
+
+        tx.send(3).unwrap();
+        assert!(effects.poll(&mut cx, &dispatcher));
+        assert_eq!(effects.len(), 0);
+        assert_eq!(received(&mut rx), [3]);
+    }
+
+    struct StartFetch;
+    struct Loaded(Vec<usize>);
+    struct Done;
+
+    struct FetchModel {
+        response: Option<tokio::sync::oneshot::Receiver<Vec<usize>>>,
+        items: Vec<usize>,
+        done: bool,
+    }
+
+    impl crate::Reduce for FetchModel {
+        fn reduce(mut self, event: &dyn Any) -> Self {
+            if event.is::<StartFetch>() {
+                let response = self.response.take().unwrap();
+                command(Cmd::perform(async move { Loaded(response.await.unwrap()) }));
+            }
+            if let Some(Loaded(items)) = event.downcast_ref() {
+                self.items = items.clone();
+                command(Cmd::dispatch(Done));
+            }
+            if event.is::<Done>() {
+                self.done = true;
+            }
+            self
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ItemsView {
+        items: Vec<usize>,
+    }
+
+    impl crate::Render for ItemsView {
+        fn render(&self) -> crate::Element {
+            crate::fragment(
+                self.items
+                    .iter()
+                    .map(|item| crate::li((), item.to_string())),
+            )
+        }
+    }
+
+    #[test]
+    fn commands_from_a_reducer_feed_their_events_back_into_the_loop() {
+        let (tx, response) = tokio::sync::oneshot::channel();
+        let mut app = HeadlessApp::new(
+            FetchModel {
+                response: Some(response),
+                items: vec![],
+                done: false,
+            },
+            |model: &FetchModel| ItemsView {
+                items: model.items.clone(),
+            },
+        );
+
+        app.dispatch(StartFetch);
+        app.flush();
+        assert_eq!(app.pending_effects(), 1);
+        assert_eq!(app.html(), "");
+
+        tx.send(vec![1, 2]).unwrap();
+        app.flush();
+        assert_eq!(app.pending_effects(), 0);
+        assert_eq!(app.html(), "<li>1</li><li>2</li>");
+        assert!(app.model().done);
+    }
+}
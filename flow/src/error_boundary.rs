@@ -0,0 +1,244 @@
+use crate::{Element, IntoElement, Render};
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+/// Why a view failed to render, mount or update.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderError {
+    message: String,
+}
+
+impl RenderError {
+    pub fn new(message: impl ToString) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            Self::new(message)
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            Self::new(message)
+        } else {
+            Self::new("render panicked")
+        }
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Renders `children`, or `fallback` if any of them fails to render, or panics in `render`,
+/// `on_mount` or `on_unmount`.
+///
+/// A failure is reported to the boundary's `on_error` handler, set with `Element::event`,
+/// and never reaches the views outside the boundary.
+/// The children are tried again whenever the boundary's props change.
+/// Failures in `fallback` are left to the next boundary up.
+///
+/// Panics are only caught on targets that unwind. On `wasm32` a panic aborts,
+/// so views that can fail there should return a `RenderError` from `Render::try_render`.
+pub fn error_boundary(fallback: impl IntoElement, children: impl IntoElement) -> Element {
+    Element::single(Box::new(ErrorBoundary {
+        fallback: fallback.into_element(),
+        children: children.into_element(),
+    }))
+}
+
+#[derive(Clone, PartialEq)]
+pub(crate) struct ErrorBoundary {
+    pub(crate) fallback: Element,
+    children: Element,
+}
+
+impl Render for ErrorBoundary {
+    fn render(&self) -> Element {
+        self.children.clone()
+    }
+}
+
+thread_local! {
+    static ERRORS: RefCell<Option<Vec<RenderError>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, turning a panic into an error. Panics abort on `wasm32`, so this only
+/// catches them on native targets, such as in `HeadlessApp` tests.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, RenderError> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(RenderError::from_panic)
+}
+
+/// Runs `f` and reports a panic in it with `report_error`.
+pub(crate) fn report_panic(f: impl FnOnce()) {
+    if let Err(error) = catch_panic(f) {
+        report_error(error);
+    }
+}
+
+/// Hands `error` to the boundary whose children are being updated, or logs it if there is none.
+pub(crate) fn report_error(error: RenderError) {
+    ERRORS.with(|errors| match errors.borrow_mut().as_mut() {
+        Some(errors) => errors.push(error),
+        None => crate::error!("Render failed outside of an error boundary: {}", error),
+    });
+}
+
+/// Runs `update` and returns the errors reported in it.
+pub(crate) fn collect_errors<T>(update: impl FnOnce() -> T) -> (T, Vec<RenderError>) {
+    let outer = ERRORS.with(|errors| errors.replace(Some(vec![])));
+    let output = update();
+    let errors = ERRORS.with(|errors| errors.replace(outer));
+    (output, errors.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dom::headless::HeadlessApp, li, span, Reduce};
+
+    #[test]
+    fn catch_panic_keeps_the_panic_message() {
+        assert_eq!(catch_panic(|| 1), Ok(1));
+        assert_eq!(
+            catch_panic(|| panic!("cannot render")),
+            Err::<(), _>(RenderError::new("cannot render"))
+        );
+        assert_eq!(
+            catch_panic(|| panic!("cannot render {}", 1)),
+            Err::<(), _>(RenderError::new("cannot render 1"))
+        );
+    }
+
+    #[test]
+    fn errors_are_collected_by_the_innermost_update() {
+        let ((inner, ()), outer) = collect_errors(|| {
+            report_error(RenderError::new("outer"));
+            let (_, inner) = collect_errors(|| report_panic(|| panic!("inner")));
+            (inner, report_error(RenderError::new("after")))
+        });
+
+        assert_eq!(inner, vec![RenderError::new("inner")]);
+        assert_eq!(
+            outer,
+            vec![RenderError::new("outer"), RenderError::new("after")]
+        );
+    }
+
+    struct Model {
+        label: &'static str,
+        errors: Vec<String>,
+    }
+
+    struct SetLabel(&'static str);
+
+    struct Failed(String);
+
+    struct Explode;
+
+    impl Reduce for Model {
+        fn reduce(mut self, event: &dyn Any) -> Self {
+            if event.is::<Explode>() {
+                self.label = "exploded";
+                todo!();
+            }
+            if let Some(SetLabel(label)) = event.downcast_ref() {
+                self.label = label;
+            }
+            if let Some(Failed(error)) = event.downcast_ref() {
+                self.errors.push(error.clone());
+            }
+            self
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Fragile {
+        label: &'static str,
+    }
+
+    impl Render for Fragile {
+        fn render(&self) -> Element {
+            if self.label == "panic" {
+                panic!("cannot render {}", self.label);
+            }
+            li((), self.label)
+        }
+        fn try_render(&self) -> Result<Element, RenderError> {
+            if self.label == "error" {
+                return Err(RenderError::new("invalid label"));
+            }
+            Ok(self.render())
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct View {
+        label: &'static str,
+    }
+
+    impl Render for View {
+        fn render(&self) -> Element {
+            let boundary = error_boundary(span((), "fallback"), Fragile { label: self.label })
+                .event(|builder| {
+                    builder.on_error_fn(|error| Some(Failed(error.message().to_string())));
+                });
+            crate::render((boundary, li((), "sibling")))
+        }
+    }
+
+    #[test]
+    fn renders_the_fallback_and_reports_failures() {
+        let mut app = HeadlessApp::new(
+            Model {
+                label: "a",
+                errors: vec![],
+            },
+            |model: &Model| View { label: model.label },
+        );
+        assert_eq!(app.html(), "<li>a</li><li>sibling</li>");
+
+        app.dispatch(SetLabel("panic"));
+        app.flush();
+        assert_eq!(app.html(), "<span>fallback</span><li>sibling</li>");
+
+        app.dispatch(SetLabel("b"));
+        app.flush();
+        assert_eq!(app.html(), "<li>b</li><li>sibling</li>");
+
+        app.dispatch(SetLabel("error"));
+        app.flush();
+        assert_eq!(app.html(), "<span>fallback</span><li>sibling</li>");
+        app.flush();
+        assert_eq!(
+            app.model().errors,
+            vec!["cannot render panic", "invalid label"]
+        );
+    }
+
+    #[test]
+    fn a_reducer_that_loses_the_model_shuts_the_loop_down() {
+        let mut app = HeadlessApp::new(
+            Model {
+                label: "a",
+                errors: vec![],
+            },
+            |model: &Model| View { label: model.label },
+        );
+
+        app.dispatch(Explode);
+        app.dispatch(SetLabel("b"));
+        app.flush();
+        assert_eq!(app.html(), "");
+    }
+}
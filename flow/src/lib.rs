@@ -1,14 +1,38 @@
+extern crate self as flow;
+
+mod context;
+mod dispatcher;
 pub mod dom;
+mod effect;
+mod error_boundary;
+mod local_state;
+mod memo;
 pub mod prelude;
 mod reduce;
 mod render;
 mod render_tree;
 mod start;
+mod subscription;
 
+pub(crate) use context::ContextScope;
+pub use context::{provide_context, use_context};
+pub use dispatcher::Dispatcher;
+pub(crate) use dispatcher::Shutdown;
 pub use dom::*;
+pub use effect::*;
+pub(crate) use error_boundary::{
+    catch_panic, collect_errors, report_error, report_panic, ErrorBoundary,
+};
+pub use error_boundary::{error_boundary, RenderError};
+pub use flow_macros::{component, rsx, Reduce};
+pub(crate) use local_state::{any_dirty_slot, current_slot, LocalStateSlot};
+pub use local_state::{LocalDispatcher, LocalEvent, StatefulRender};
+pub use memo::*;
 pub use reduce::*;
 pub use render::*;
 pub use start::*;
+pub use subscription::Subscription;
+pub(crate) use subscription::Subscriptions;
 
 pub fn default<T: Default>() -> T {
     T::default()
@@ -16,14 +40,20 @@ pub fn default<T: Default>() -> T {
 
 #[macro_export]
 macro_rules! log {
-    ($($arg:tt)*) => {
+    ($($arg:tt)*) => {{
+        #[cfg(target_arch = "wasm32")]
         web_sys::console::log_1(&format_args!($($arg)*).to_string().into());
-    };
+        #[cfg(not(target_arch = "wasm32"))]
+        println!($($arg)*);
+    }};
 }
 
 #[macro_export]
 macro_rules! error {
-    ($($arg:tt)*) => {
+    ($($arg:tt)*) => {{
+        #[cfg(target_arch = "wasm32")]
         web_sys::console::error_1(&format_args!($($arg)*).to_string().into());
-    };
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!($($arg)*);
+    }};
 }
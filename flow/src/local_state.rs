@@ -0,0 +1,390 @@
+use crate::{ContextScope, Dispatcher, Element, Reduce, Render};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+/// A component with state of its own, kept on its node in the render tree instead of the model.
+///
+/// The state is created by `init_state` when the component mounts, survives prop updates,
+/// and is dropped when the component unmounts. Events sent with the `LocalDispatcher`
+/// are reduced by `State`'s `Reduce` in the `start` loop, in order with every other event,
+/// and re-render only this component.
+pub trait StatefulRender: Clone + PartialEq + 'static {
+    type State: Reduce + 'static;
+
+    fn init_state(&self) -> Self::State;
+    fn render(&self, state: &Self::State, local: &LocalDispatcher) -> Element;
+    fn on_mount(&self) {}
+    fn on_unmount(&self) {}
+}
+
+impl<T: StatefulRender> Render for T {
+    fn render(&self) -> Element {
+        let (slot, dispatcher) = CURRENT_SLOT
+            .with(|current| current.borrow().clone())
+            .expect("StatefulRender rendered outside of the render tree");
+        let mut state = slot.state.borrow_mut();
+        let state = state.get_or_insert_with(|| Box::new(self.init_state()));
+        let local = LocalDispatcher {
+            slot: Rc::downgrade(&slot),
+            dispatcher,
+        };
+        StatefulRender::render(self, state.as_any().downcast_ref().unwrap(), &local)
+    }
+    fn on_mount(&self) {
+        StatefulRender::on_mount(self)
+    }
+    fn on_unmount(&self) {
+        StatefulRender::on_unmount(self)
+    }
+}
+
+/// Sends events to the state of the component that is rendering.
+#[derive(Clone)]
+pub struct LocalDispatcher {
+    slot: Weak<LocalStateSlot>,
+    dispatcher: Dispatcher,
+}
+
+impl LocalDispatcher {
+    /// An event for `on_click` and the like that is reduced by this component's state
+    /// instead of the model.
+    pub fn event<Event: Any + PartialEq>(&self, event: Event) -> LocalEvent {
+        LocalEvent {
+            slot: self.slot.clone(),
+            event: Rc::new(event),
+            equals: |event, other| event.downcast_ref::<Event>() == other.downcast_ref(),
+        }
+    }
+    pub fn dispatch(&self, event: impl Any + PartialEq) {
+        self.dispatcher.dispatch(self.event(event));
+    }
+}
+
+/// An event addressed to the state of one mounted component.
+/// Dropped without effect if the component has unmounted by the time it is reduced.
+#[derive(Clone)]
+pub struct LocalEvent {
+    slot: Weak<LocalStateSlot>,
+    event: Rc<dyn Any>,
+    equals: fn(&dyn Any, &dyn Any) -> bool,
+}
+
+impl PartialEq for LocalEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.slot.ptr_eq(&other.slot) && (self.equals)(self.event.as_ref(), other.event.as_ref())
+    }
+}
+
+impl LocalEvent {
+    /// Reduces the event into its component's state. Returns whether the component is mounted.
+    pub(crate) fn reduce(&self) -> bool {
+        let Some(slot) = self.slot.upgrade() else {
+            return false;
+        };
+        let mut state = slot.state.borrow_mut();
+        let Some(old_state) = state.take() else {
+            return false;
+        };
+        // Marked first, so a reducer that panics re-renders the component with a fresh state.
+        slot.mark_dirty();
+        *state = Some(old_state.reduce_box(self.event.as_ref()));
+        true
+    }
+}
+
+trait AnyReduce {
+    fn reduce_box(self: Box<Self>, event: &dyn Any) -> Box<dyn AnyReduce>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<State: Reduce + 'static> AnyReduce for State {
+    fn reduce_box(self: Box<Self>, event: &dyn Any) -> Box<dyn AnyReduce> {
+        Box::new((*self).reduce(event))
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+thread_local! {
+    static CURRENT_SLOT: RefCell<Option<(Rc<LocalStateSlot>, Dispatcher)>> =
+        const { RefCell::new(None) };
+    /// Number of slots whose state changed since their component last rendered.
+    static DIRTY_SLOTS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// What the component of one node keeps between renders: its local state, if it is a
+/// `StatefulRender`, and the contexts it reads or provides.
+pub(crate) struct LocalStateSlot {
+    state: RefCell<Option<Box<dyn AnyReduce>>>,
+    dirty: Cell<bool>,
+    /// Contexts provided by the ancestors.
+    pub(crate) contexts: Option<Rc<ContextScope>>,
+    /// The context this node provides to its children.
+    pub(crate) provided: RefCell<Option<Rc<ContextScope>>>,
+}
+
+impl LocalStateSlot {
+    pub(crate) fn new(contexts: Option<Rc<ContextScope>>) -> Self {
+        Self {
+            state: RefCell::new(None),
+            dirty: Cell::new(false),
+            contexts,
+            provided: RefCell::new(None),
+        }
+    }
+
+    /// Runs `render` with this slot as the state of the rendering component,
+    /// which clears its changes.
+    pub(crate) fn render<T>(
+        self: &Rc<Self>,
+        dispatcher: &Dispatcher,
+        render: impl FnOnce() -> T,
+    ) -> T {
+        self.take_dirty();
+        let outer =
+            CURRENT_SLOT.with(|current| current.replace(Some((self.clone(), dispatcher.clone()))));
+        let output = render();
+        CURRENT_SLOT.with(|current| current.replace(outer));
+        output
+    }
+
+    /// Drops the state, for when the component unmounts.
+    pub(crate) fn clear(&self) {
+        self.state.borrow_mut().take();
+        self.take_dirty();
+    }
+
+    /// Returns whether the state changed since the component last rendered, and resets it.
+    pub(crate) fn take_dirty(&self) -> bool {
+        let dirty = self.dirty.replace(false);
+        if dirty {
+            DIRTY_SLOTS.with(|count| count.set(count.get() - 1));
+        }
+        dirty
+    }
+
+    pub(crate) fn mark_dirty(&self) {
+        if !self.dirty.replace(true) {
+            DIRTY_SLOTS.with(|count| count.set(count.get() + 1));
+        }
+    }
+}
+
+impl Drop for LocalStateSlot {
+    fn drop(&mut self) {
+        self.take_dirty();
+    }
+}
+
+/// The slot of the component that is rendering.
+pub(crate) fn current_slot() -> Option<Rc<LocalStateSlot>> {
+    CURRENT_SLOT.with(|current| current.borrow().as_ref().map(|(slot, _)| slot.clone()))
+}
+
+/// Whether any mounted component has local state changes to render.
+pub(crate) fn any_dirty_slot() -> bool {
+    DIRTY_SLOTS.with(|count| count.get() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::headless::HeadlessApp;
+
+    #[derive(Clone, PartialEq)]
+    struct Increment;
+
+    struct Clicks(usize);
+
+    impl Reduce for Clicks {
+        fn reduce(self, event: &dyn Any) -> Self {
+            match event.downcast_ref::<Increment>() {
+                Some(Increment) => Clicks(self.0 + 1),
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct ClickCounter {
+        start: usize,
+    }
+
+    impl StatefulRender for ClickCounter {
+        type State = Clicks;
+
+        fn init_state(&self) -> Clicks {
+            Clicks(self.start)
+        }
+        fn render(&self, clicks: &Clicks, _local: &LocalDispatcher) -> Element {
+            Element::keyed(clicks.0, ())
+        }
+    }
+
+    /// Renders `counter` in `slot` and returns its count and an `Increment` for its state.
+    fn render_counter(
+        slot: &Rc<LocalStateSlot>,
+        counter: &ClickCounter,
+    ) -> (Option<String>, LocalEvent) {
+        let dispatcher = Dispatcher::new();
+        let element = slot.render(&dispatcher, || Render::render(counter));
+        let local = LocalDispatcher {
+            slot: Rc::downgrade(slot),
+            dispatcher,
+        };
+        (element.key().map(str::to_string), local.event(Increment))
+    }
+
+    #[test]
+    fn state_survives_prop_updates_and_resets_when_cleared() {
+        let slot = Rc::new(LocalStateSlot::new(None));
+        let (count, increment) = render_counter(&slot, &ClickCounter { start: 1 });
+        assert_eq!(count.as_deref(), Some("1"));
+
+        assert!(increment.reduce());
+        assert!(increment.reduce());
+        let (count, _) = render_counter(&slot, &ClickCounter { start: 5 });
+        assert_eq!(count.as_deref(), Some("3"));
+
+        slot.clear();
+        let (count, _) = render_counter(&slot, &ClickCounter { start: 5 });
+        assert_eq!(count.as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn reduce_marks_the_slot_dirty_until_it_renders() {
+        let slot = Rc::new(LocalStateSlot::new(None));
+        let (_, increment) = render_counter(&slot, &ClickCounter { start: 0 });
+        assert!(!any_dirty_slot());
+
+        increment.reduce();
+        increment.reduce();
+        assert!(any_dirty_slot());
+
+        render_counter(&slot, &ClickCounter { start: 0 });
+        assert!(!any_dirty_slot());
+        assert!(!slot.take_dirty());
+    }
+
+    #[test]
+    fn events_for_an_unmounted_component_are_dropped() {
+        let slot = Rc::new(LocalStateSlot::new(None));
+        let (_, increment) = render_counter(&slot, &ClickCounter { start: 0 });
+
+        slot.clear();
+        assert!(!increment.reduce());
+
+        drop(slot);
+        assert!(!increment.reduce());
+        assert!(!any_dirty_slot());
+    }
+
+    #[test]
+    fn local_events_are_equal_for_the_same_slot_and_event() {
+        let slot = Rc::new(LocalStateSlot::new(None));
+        let (_, increment) = render_counter(&slot, &ClickCounter { start: 0 });
+        let (_, again) = render_counter(&slot, &ClickCounter { start: 0 });
+        let (_, other) = render_counter(
+            &Rc::new(LocalStateSlot::new(None)),
+            &ClickCounter { start: 0 },
+        );
+
+        assert!(increment == again);
+        assert!(increment != other);
+    }
+
+    struct PanelModel {
+        label: &'static str,
+    }
+
+    struct SetLabel(&'static str);
+
+    impl Reduce for PanelModel {
+        fn reduce(self, event: &dyn Any) -> Self {
+            match event.downcast_ref::<SetLabel>() {
+                Some(SetLabel(label)) => PanelModel { label },
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct LabeledCounter {
+        label: &'static str,
+    }
+
+    impl StatefulRender for LabeledCounter {
+        type State = Clicks;
+
+        fn init_state(&self) -> Clicks {
+            Clicks(0)
+        }
+        fn render(&self, clicks: &Clicks, local: &LocalDispatcher) -> Element {
+            crate::li(
+                crate::on_click(local.event(Increment)),
+                format!("{} {}", self.label, clicks.0),
+            )
+        }
+    }
+
+    thread_local! {
+        /// Renders of `Panel` and `Sibling`, which a local event must not re-render.
+        static OTHER_RENDERS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Sibling;
+
+    impl Render for Sibling {
+        fn render(&self) -> Element {
+            OTHER_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            crate::span((), "sibling")
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Panel {
+        label: &'static str,
+    }
+
+    impl Render for Panel {
+        fn render(&self) -> Element {
+            OTHER_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            let label = self.label;
+            crate::render((
+                (!label.is_empty()).then_some(LabeledCounter { label }),
+                Sibling,
+            ))
+        }
+    }
+
+    #[test]
+    fn state_lives_on_its_node_in_the_render_tree() {
+        let mut app = HeadlessApp::new(PanelModel { label: "a" }, |model: &PanelModel| Panel {
+            label: model.label,
+        });
+        assert_eq!(app.html(), "<li>a 0</li><span>sibling</span>");
+
+        app.root().find_all("li")[0].click();
+        app.root().find_all("li")[0].click();
+        app.flush();
+        assert_eq!(app.html(), "<li>a 2</li><span>sibling</span>");
+        assert_eq!(OTHER_RENDERS.with(Cell::get), 2);
+
+        app.dispatch(SetLabel("b"));
+        app.flush();
+        assert_eq!(app.html(), "<li>b 2</li><span>sibling</span>");
+
+        app.dispatch(SetLabel(""));
+        app.flush();
+        assert_eq!(app.html(), "<span>sibling</span>");
+
+        app.dispatch(SetLabel("c"));
+        app.flush();
+        assert_eq!(app.html(), "<li>c 0</li><span>sibling</span>");
+    }
+}
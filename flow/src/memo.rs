@@ -0,0 +1,171 @@
+use std::{cell::RefCell, ops::Deref, rc::Rc};
+
+/// The last output computed from an input, recomputed only when the input changes.
+///
+/// Clones share the cache, and any two `Memo`s compare equal,
+/// so a view can keep one as a field without it affecting the diff.
+pub struct Memo<Input, Output> {
+    last: Rc<RefCell<Option<(Input, Output)>>>,
+}
+
+impl<Input: PartialEq, Output: Clone> Memo<Input, Output> {
+    pub fn new() -> Self {
+        Self {
+            last: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Returns the cached output if `input` equals the previous one,
+    /// otherwise caches and returns `compute(&input)`.
+    pub fn get(&self, input: Input, compute: impl FnOnce(&Input) -> Output) -> Output {
+        let mut last = self.last.borrow_mut();
+        match last.as_ref() {
+            Some((last_input, output)) if *last_input == input => output.clone(),
+            _ => {
+                let output = compute(&input);
+                *last = Some((input, output.clone()));
+                output
+            }
+        }
+    }
+}
+
+impl<Input: PartialEq, Output: Clone> Default for Memo<Input, Output> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Input, Output> Clone for Memo<Input, Output> {
+    fn clone(&self) -> Self {
+        Self {
+            last: self.last.clone(),
+        }
+    }
+}
+
+impl<Input, Output> PartialEq for Memo<Input, Output> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Memoizes `compute` on what `input` selects from the model, like a reselect selector.
+///
+/// The result is itself a `Fn(&Model) -> Output`,
+/// so it can be passed as `to_view` or used as the `input` of another selector.
+pub fn selector<Model, Input: PartialEq, Output: Clone>(
+    input: impl Fn(&Model) -> Input,
+    compute: impl Fn(&Input) -> Output,
+) -> impl Fn(&Model) -> Output {
+    let memo = Memo::new();
+    move |model| memo.get(input(model), &compute)
+}
+
+/// Compares by pointer instead of by value, to memoize on the identity of shared data.
+///
+/// Reducers that update the data with `Rc::make_mut` get a new allocation
+/// while a memo still holds the old one, so a change is never missed.
+pub struct ByAddress<T: ?Sized>(pub Rc<T>);
+
+impl<T: ?Sized> Clone for ByAddress<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> PartialEq for ByAddress<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: ?Sized> Deref for ByAddress<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn get_recomputes_only_when_the_input_changes() {
+        let memo = Memo::new();
+        let computed = Cell::new(0);
+        let compute = |input: &usize| {
+            computed.set(computed.get() + 1);
+            input * 2
+        };
+
+        assert_eq!(memo.get(1, compute), 2);
+        assert_eq!(memo.get(1, compute), 2);
+        assert_eq!(computed.get(), 1);
+
+        assert_eq!(memo.get(2, compute), 4);
+        assert_eq!(computed.get(), 2);
+    }
+
+    #[test]
+    fn clones_share_the_cache() {
+        let memo = Memo::new();
+        memo.get(1, |input: &usize| input * 2);
+
+        let clone = memo.clone();
+        assert_eq!(clone.get(1, |_| unreachable!()), 2);
+        assert!(memo == Memo::new());
+    }
+
+    #[test]
+    fn selector_recomputes_only_when_its_input_changes() {
+        let computed = Cell::new(0);
+        let label = selector(
+            |model: &(&'static str, usize)| model.0,
+            |label: &&'static str| {
+                computed.set(computed.get() + 1);
+                label.to_uppercase()
+            },
+        );
+
+        assert_eq!(label(&("a", 0)), "A");
+        assert_eq!(label(&("a", 1)), "A");
+        assert_eq!(computed.get(), 1);
+
+        assert_eq!(label(&("b", 1)), "B");
+        assert_eq!(computed.get(), 2);
+    }
+
+    #[test]
+    fn selectors_compose() {
+        let computed = Cell::new(0);
+        let length = selector(
+            selector(
+                |model: &(Vec<usize>, usize)| model.0.clone(),
+                |items| items.len(),
+            ),
+            |length: &usize| {
+                computed.set(computed.get() + 1);
+                *length
+            },
+        );
+
+        assert_eq!(length(&(vec![1, 2], 0)), 2);
+        assert_eq!(length(&(vec![3, 4], 1)), 2);
+        assert_eq!(computed.get(), 1);
+    }
+
+    #[test]
+    fn by_address_compares_by_pointer() {
+        let items = Rc::new(vec![1, 2]);
+        let same = ByAddress(items.clone());
+        let equal = ByAddress(Rc::new(vec![1, 2]));
+
+        assert!(ByAddress(items.clone()) == same);
+        assert!(ByAddress(items) != equal);
+        assert_eq!(equal.len(), 2);
+    }
+}
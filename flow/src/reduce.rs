@@ -1,3 +1,110 @@
+use std::any::Any;
+
 pub trait Reduce {
-    fn reduce(self, event: &dyn std::any::Any) -> Self;
+    fn reduce(self, event: &dyn Any) -> Self;
+}
+
+/// Handles one event type of a model, called by `#[derive(Reduce)]` for each type listed in
+/// `#[reduce(events(..))]`.
+pub trait ReduceEvent<Event: 'static>: Sized {
+    fn reduce_event(self, event: &Event) -> Self;
+}
+
+/// A reducer that updates the model behind a reference instead of moving it.
+pub trait UpdateInPlace {
+    /// Returns whether the model changed, so the view is only rebuilt when it did.
+    fn reduce_mut(&mut self, event: &dyn Any) -> bool;
+}
+
+/// How `start` applies events to a model: `ByReduce` for `Reduce` models and
+/// `ByUpdateInPlace` for `UpdateInPlace` models. `Kind` is inferred.
+pub trait Update<Kind>: Sized {
+    /// Applies `event` to the model in `slot` and returns whether it may have changed.
+    fn update(slot: &mut Option<Self>, event: &dyn Any) -> bool;
+}
+
+pub enum ByReduce {}
+pub enum ByUpdateInPlace {}
+
+/// The model is moved into the reducer, so a reducer that panics leaves `slot` empty.
+/// Models that must survive a panicking reducer implement `UpdateInPlace` instead.
+impl<Model: Reduce> Update<ByReduce> for Model {
+    fn update(slot: &mut Option<Self>, event: &dyn Any) -> bool {
+        let Some(model) = slot.take() else {
+            return false;
+        };
+        *slot = Some(model.reduce(event));
+        true
+    }
+}
+
+impl<Model: UpdateInPlace> Update<ByUpdateInPlace> for Model {
+    fn update(slot: &mut Option<Self>, event: &dyn Any) -> bool {
+        slot.as_mut().is_some_and(|model| model.reduce_mut(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(usize);
+
+    struct Increment;
+    struct Fail;
+
+    impl Reduce for Counter {
+        fn reduce(self, event: &dyn Any) -> Self {
+            if event.is::<Fail>() {
+                panic!("cannot reduce");
+            }
+            match event.downcast_ref::<Increment>() {
+                Some(Increment) => Counter(self.0 + 1),
+                None => self,
+            }
+        }
+    }
+
+    impl UpdateInPlace for Counter {
+        fn reduce_mut(&mut self, event: &dyn Any) -> bool {
+            if event.is::<Fail>() {
+                panic!("cannot reduce");
+            }
+            self.0 += 1;
+            true
+        }
+    }
+
+    fn update_panics<Kind>(slot: &mut Option<Counter>) -> bool
+    where
+        Counter: Update<Kind>,
+    {
+        catch_unwind(AssertUnwindSafe(|| Counter::update(slot, &Fail))).is_err()
+    }
+
+    #[test]
+    fn a_panicking_reduce_empties_the_slot() {
+        let mut slot = Some(Counter(0));
+        assert!(<Counter as Update<ByReduce>>::update(&mut slot, &Increment));
+        assert_eq!(slot, Some(Counter(1)));
+
+        assert!(update_panics::<ByReduce>(&mut slot));
+        assert_eq!(slot, None);
+        assert!(!<Counter as Update<ByReduce>>::update(
+            &mut slot, &Increment
+        ));
+    }
+
+    #[test]
+    fn a_panicking_reduce_mut_keeps_the_model() {
+        let mut slot = Some(Counter(0));
+        assert!(<Counter as Update<ByUpdateInPlace>>::update(
+            &mut slot, &Increment
+        ));
+
+        assert!(update_panics::<ByUpdateInPlace>(&mut slot));
+        assert_eq!(slot, Some(Counter(1)));
+    }
 }
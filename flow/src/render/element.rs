@@ -1,30 +1,41 @@
 use super::*;
+use std::rc::Rc;
 
+/// Children are shared, so cloning an element, as views do to return their children
+/// from `render`, does not copy the subtree. Children that nothing else shares are moved
+/// into the render tree instead of cloned.
 pub enum Element {
-    Single { box_render: Box<dyn Render> },
-    Multiple { elements: Vec<Element> },
+    Single {
+        box_render: Box<dyn Render>,
+        event_handlers: EventHandlers,
+    },
+    Multiple {
+        elements: Rc<Vec<Element>>,
+    },
+    Keyed {
+        key: String,
+        element: Rc<Element>,
+    },
 }
 
 impl Element {
-    pub(crate) fn on_mount(&self) {
-        match self {
-            Element::Single { box_render } => box_render.on_mount(),
-            Element::Multiple { elements } => {
-                for element in elements {
-                    element.on_mount();
-                }
-            }
+    pub(crate) fn single(box_render: Box<dyn Render>) -> Element {
+        Element::Single {
+            box_render,
+            event_handlers: EventHandlers::default(),
+        }
+    }
+    /// Tags `element` with `key` so siblings are reconciled by key instead of by index.
+    pub fn keyed(key: impl ToString, element: impl IntoElement) -> Element {
+        Element::Keyed {
+            key: key.to_string(),
+            element: Rc::new(element.into_element()),
         }
     }
-    #[allow(dead_code)]
-    pub(crate) fn on_unmount(&self) {
+    pub(crate) fn key(&self) -> Option<&str> {
         match self {
-            Element::Single { box_render } => box_render.on_unmount(),
-            Element::Multiple { elements } => {
-                for element in elements {
-                    element.on_unmount();
-                }
-            }
+            Element::Keyed { key, .. } => Some(key),
+            _ => None,
         }
     }
 }
@@ -32,48 +43,55 @@ impl Element {
 impl Clone for Element {
     fn clone(&self) -> Self {
         match self {
-            Element::Single { box_render } => Element::Single {
+            Element::Single {
+                box_render,
+                event_handlers,
+            } => Element::Single {
                 box_render: box_render.clone_box(),
+                event_handlers: event_handlers.clone(),
             },
             Element::Multiple { elements } => Element::Multiple {
                 elements: elements.clone(),
             },
+            Element::Keyed { key, element } => Element::Keyed {
+                key: key.clone(),
+                element: element.clone(),
+            },
         }
     }
 }
 
+/// Event handlers are closures and cannot be compared, so an element that has any is never
+/// equal. Otherwise a view holding it as a child would skip re-rendering and keep the
+/// handlers from the previous render, with whatever they captured.
 impl PartialEq for Element {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Element::Single { box_render }, Element::Single { box_render: other }) => {
-                box_render.equals(other.as_ref())
+            (
+                Element::Single {
+                    box_render,
+                    event_handlers,
+                },
+                Element::Single {
+                    box_render: other,
+                    event_handlers: other_handlers,
+                },
+            ) => {
+                event_handlers.is_empty()
+                    && other_handlers.is_empty()
+                    && box_render.equals(other.as_ref())
             }
             (Element::Multiple { elements }, Element::Multiple { elements: other }) => {
-                elements == other
+                Rc::ptr_eq(elements, other) || elements == other
             }
+            (
+                Element::Keyed { key, element },
+                Element::Keyed {
+                    key: other_key,
+                    element: other,
+                },
+            ) => key == other_key && (Rc::ptr_eq(element, other) || element == other),
             _ => false,
         }
     }
 }
-
-impl Element {
-    pub fn event(self, build: impl FnOnce(&mut EventBuilder)) -> Element {
-        render(())
-    }
-}
-
-pub struct EventBuilder {}
-
-impl EventBuilder {
-    pub fn on_click_fn<Event: std::any::Any>(
-        &mut self,
-        handler: impl Fn(ClickEvent) -> Option<Event> + 'static,
-    ) -> Self {
-        todo!()
-    }
-    pub fn on_click<Event: std::any::Any>(&mut self, event: Event) -> Self {
-        todo!()
-    }
-}
-
-pub struct ClickEvent {}
@@ -0,0 +1,96 @@
+use super::*;
+use std::rc::Rc;
+
+type Handler<E> = Rc<dyn Fn(E) -> Option<Box<dyn Any>>>;
+
+/// Event handlers attached to an element, stored on its `Node` once mounted.
+#[derive(Clone, Default)]
+pub struct EventHandlers {
+    on_click: Option<Handler<ClickEvent>>,
+    on_error: Option<Handler<RenderError>>,
+}
+
+impl EventHandlers {
+    pub fn on_click(&self, event: ClickEvent) -> Option<Box<dyn Any>> {
+        self.on_click.as_ref().and_then(|handler| handler(event))
+    }
+    pub fn on_error(&self, error: RenderError) -> Option<Box<dyn Any>> {
+        self.on_error.as_ref().and_then(|handler| handler(error))
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.on_click.is_none() && self.on_error.is_none()
+    }
+    fn merge(&mut self, other: &EventHandlers) {
+        if other.on_click.is_some() {
+            self.on_click.clone_from(&other.on_click);
+        }
+        if other.on_error.is_some() {
+            self.on_error.clone_from(&other.on_error);
+        }
+    }
+}
+
+pub struct EventBuilder {
+    handlers: EventHandlers,
+}
+
+impl EventBuilder {
+    pub fn on_click_fn<Event: Any>(
+        &mut self,
+        handler: impl Fn(ClickEvent) -> Option<Event> + 'static,
+    ) -> &mut Self {
+        self.on_click_box(move |click_event| {
+            handler(click_event).map(|event| Box::new(event) as Box<dyn Any>)
+        })
+    }
+    pub fn on_click<Event: Any + Clone>(&mut self, event: Event) -> &mut Self {
+        self.on_click_fn(move |_| Some(event.clone()))
+    }
+    pub(crate) fn on_click_box(
+        &mut self,
+        handler: impl Fn(ClickEvent) -> Option<Box<dyn Any>> + 'static,
+    ) -> &mut Self {
+        self.handlers.on_click = Some(Rc::new(handler));
+        self
+    }
+    /// Called by an `error_boundary` when its children fail, with the error.
+    pub fn on_error_fn<Event: Any>(
+        &mut self,
+        handler: impl Fn(RenderError) -> Option<Event> + 'static,
+    ) -> &mut Self {
+        self.handlers.on_error = Some(Rc::new(move |error| {
+            handler(error).map(|event| Box::new(event) as Box<dyn Any>)
+        }));
+        self
+    }
+    pub fn on_error<Event: Any + Clone>(&mut self, event: Event) -> &mut Self {
+        self.on_error_fn(move |_| Some(event.clone()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ClickEvent {}
+
+impl Element {
+    pub fn event(mut self, build: impl FnOnce(&mut EventBuilder)) -> Element {
+        let mut builder = EventBuilder {
+            handlers: EventHandlers::default(),
+        };
+        build(&mut builder);
+        self.add_event_handlers(&builder.handlers);
+        self
+    }
+    fn add_event_handlers(&mut self, handlers: &EventHandlers) {
+        match self {
+            Element::Single { event_handlers, .. } => event_handlers.merge(handlers),
+            Element::Multiple { elements } => {
+                for element in std::rc::Rc::make_mut(elements) {
+                    element.add_event_handlers(handlers);
+                }
+            }
+            Element::Keyed { element, .. } => {
+                std::rc::Rc::make_mut(element).add_event_handlers(handlers)
+            }
+        }
+    }
+}
@@ -1,5 +1,14 @@
 use super::*;
+use std::rc::Rc;
 
+/// Anything that can be rendered as children: an `Element`, a `Render` type, a string, `()`,
+/// and tuples of up to 12, `Option`s, `Vec`s and mapped iterators of those.
+///
+/// ```compile_fail
+/// use flow::prelude::*;
+///
+/// let _ = render(("0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12"));
+/// ```
 pub trait IntoElement {
     fn into_element(self) -> Element;
 }
@@ -12,7 +21,9 @@ impl IntoElement for Element {
 
 impl IntoElement for () {
     fn into_element(self) -> Element {
-        Element::Multiple { elements: vec![] }
+        Element::Multiple {
+            elements: Rc::default(),
+        }
     }
 }
 
@@ -22,67 +33,171 @@ where
 {
     fn into_element(self) -> Element {
         Element::Multiple {
-            elements: vec![Element::Single {
-                box_render: Box::new(self),
-            }],
+            elements: Rc::new(vec![Element::single(Box::new(self))]),
         }
     }
 }
 
-impl<T0, T1> IntoElement for (T0, T1)
-where
-    T0: Render + 'static,
-    T1: Render + 'static,
-{
-    fn into_element(self) -> Element {
-        let (t0, t1) = self;
-        Element::Multiple {
-            elements: vec![
-                Element::Single {
-                    box_render: Box::new(t0),
-                },
-                Element::Single {
-                    box_render: Box::new(t1),
-                },
-            ],
+/// Each member of a tuple takes one child slot. A member that renders a single element is
+/// stored as that element, and any other member stays a nested `Element::Multiple`, so a member
+/// that changes its length does not shift the slots of the members after it.
+macro_rules! tuple_into_element {
+    ($($t:ident),+) => {
+        impl<$($t: IntoElement),+> IntoElement for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn into_element(self) -> Element {
+                let ($($t,)+) = self;
+                Element::Multiple {
+                    elements: Rc::new(vec![$(into_child($t)),+]),
+                }
+            }
         }
+    };
+}
+tuple_into_element!(T0, T1);
+tuple_into_element!(T0, T1, T2);
+tuple_into_element!(T0, T1, T2, T3);
+tuple_into_element!(T0, T1, T2, T3, T4);
+tuple_into_element!(T0, T1, T2, T3, T4, T5);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6, T7);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+tuple_into_element!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+fn into_child(member: impl IntoElement) -> Element {
+    match member.into_element() {
+        Element::Multiple { elements } if elements.len() == 1 => {
+            Rc::unwrap_or_clone(elements).pop().unwrap()
+        }
+        element => element,
     }
 }
 
-impl<T0, T1, T2> IntoElement for (T0, T1, T2)
-where
-    T0: Render + 'static,
-    T1: Render + 'static,
-    T2: Render + 'static,
-{
+impl<T: IntoElement> IntoElement for Vec<T> {
     fn into_element(self) -> Element {
-        let (t0, t1, t2) = self;
+        fragment(self)
+    }
+}
 
-        Element::Multiple {
-            elements: vec![
-                Element::Single {
-                    box_render: Box::new(t0),
-                },
-                Element::Single {
-                    box_render: Box::new(t1),
-                },
-                Element::Single {
-                    box_render: Box::new(t2),
-                },
-            ],
+impl<T: IntoElement> IntoElement for Option<T> {
+    fn into_element(self) -> Element {
+        match self {
+            Some(into_element) => into_element.into_element(),
+            None => ().into_element(),
         }
     }
 }
 
-impl<T: Render + 'static> IntoElement for Vec<T> {
+impl IntoElement for Box<dyn Render> {
     fn into_element(self) -> Element {
+        Element::single(self)
+    }
+}
+
+impl<I: Iterator, F: FnMut(I::Item) -> T, T: IntoElement> IntoElement for std::iter::Map<I, F> {
+    fn into_element(self) -> Element {
+        fragment(self)
+    }
+}
+
+/// Collects `iter` into an `Element::Multiple`, one child slot per item.
+pub fn fragment<T: IntoElement>(iter: impl IntoIterator<Item = T>) -> Element {
+    Element::Multiple {
+        elements: Rc::new(iter.into_iter().map(into_child).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq)]
+    struct Item(usize);
+
+    impl Render for Item {
+        fn render(&self) -> Element {
+            ().into_element()
+        }
+    }
+
+    fn item(index: usize) -> Element {
+        Element::single(Box::new(Item(index)))
+    }
+
+    fn multiple(elements: Vec<Element>) -> Element {
         Element::Multiple {
-            elements: self
-                .into_iter()
-                .map(|t| Element::Single {
-                    box_render: Box::new(t),
-                })
-                .collect(),
+            elements: Rc::new(elements),
         }
     }
+
+    #[test]
+    fn tuple_of_twelve_takes_one_slot_per_member() {
+        let element = (
+            Item(0),
+            item(1),
+            (),
+            vec![Item(3), Item(4)],
+            Item(4),
+            Item(5),
+            Item(6),
+            Item(7),
+            Item(8),
+            Item(9),
+            Item(10),
+            (Item(11), Item(12)),
+        )
+            .into_element();
+        assert!(
+            element
+                == multiple(vec![
+                    item(0),
+                    item(1),
+                    multiple(vec![]),
+                    multiple(vec![item(3), item(4)]),
+                    item(4),
+                    item(5),
+                    item(6),
+                    item(7),
+                    item(8),
+                    item(9),
+                    item(10),
+                    multiple(vec![item(11), item(12)]),
+                ])
+        );
+    }
+
+    #[test]
+    fn option_renders_its_value_or_nothing() {
+        assert!(Some(Item(0)).into_element() == Item(0).into_element());
+        assert!(None::<Item>.into_element() == multiple(vec![]));
+    }
+
+    #[test]
+    fn vec_and_mapped_iterator_take_one_slot_per_item() {
+        let expected = multiple(vec![item(0), item(1)]);
+        assert!(vec![Item(0), Item(1)].into_element() == expected);
+        assert!((0..2).map(Item).into_element() == expected);
+        assert!(fragment([Item(0), Item(1)]) == expected);
+    }
+
+    #[test]
+    fn nested_vecs_stay_nested() {
+        let element = vec![vec![Item(0), Item(1)], vec![], vec![Item(2)]].into_element();
+        assert!(
+            element
+                == multiple(vec![
+                    multiple(vec![item(0), item(1)]),
+                    multiple(vec![]),
+                    item(2)
+                ])
+        );
+    }
+
+    #[test]
+    fn boxed_render_is_a_single_element() {
+        let boxed: Box<dyn Render> = Box::new(Item(0));
+        assert!(boxed.into_element() == item(0));
+    }
 }
@@ -1,17 +1,53 @@
 mod element;
+mod event;
 mod into_element;
 
+use crate::RenderError;
 pub use element::Element;
-pub use into_element::IntoElement;
+pub use event::*;
+pub use into_element::{fragment, IntoElement};
 use std::any::Any;
 
+/// A view whose props are compared with `PartialEq` to skip re-rendering unchanged subtrees.
+///
+/// `render` borrows the view so the render tree can call it on the node it keeps, without
+/// cloning the props. Implementations written against the old `render(self: Box<Self>)`
+/// migrate by taking `&self` and cloning only the fields they move into the returned `Element`,
+/// or keep compiling in the meantime by implementing `RenderBoxed` instead.
 pub trait Render: AnyEqual + CloneBox {
-    #[deprecated(note = "Please do not use this method.")]
-    fn render(self: Box<Self>) -> Element;
+    fn render(&self) -> Element;
+    /// What the render tree calls to render the view. Views that can fail override it
+    /// to return an error, which is caught by the nearest `error_boundary`.
+    ///
+    /// This is the supported way to fail: a panic in `render` is only caught on native targets,
+    /// and aborts on `wasm32`.
+    fn try_render(&self) -> Result<Element, RenderError> {
+        Ok(self.render())
+    }
     fn on_mount(&self) {}
     fn on_unmount(&self) {}
 }
 
+/// The old `Render` signature, which consumed a clone of the view on every render.
+///
+/// Wrap such a view in `RenderBoxedView` to use it where a `Render` is expected.
+#[deprecated(note = "implement `Render` with `fn render(&self)` instead")]
+pub trait RenderBoxed: Clone + PartialEq + 'static {
+    fn render(self: Box<Self>) -> Element;
+}
+
+/// Renders a `RenderBoxed` view by cloning it, as the render tree used to.
+#[deprecated(note = "implement `Render` with `fn render(&self)` instead")]
+#[derive(Clone, PartialEq)]
+pub struct RenderBoxedView<T>(pub T);
+
+#[allow(deprecated)]
+impl<T: RenderBoxed> Render for RenderBoxedView<T> {
+    fn render(&self) -> Element {
+        RenderBoxed::render(Box::new(self.0.clone()))
+    }
+}
+
 pub trait AnyEqual {
     fn as_any(&self) -> &dyn Any;
     fn equals(&self, _: &dyn Render) -> bool;
@@ -26,7 +62,7 @@ impl<S: 'static + PartialEq> AnyEqual for S {
         other
             .as_any()
             .downcast_ref::<S>()
-            .map_or(false, |a| self == a)
+            .is_some_and(|a| self == a)
     }
 }
 
@@ -43,3 +79,35 @@ impl<S: 'static + Clone + Render> CloneBox for S {
 pub fn render(into_element: impl IntoElement) -> Element {
     into_element.into_element()
 }
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq)]
+    struct Item(usize);
+
+    impl Render for Item {
+        fn render(&self) -> Element {
+            ().into_element()
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct OldList {
+        items: Vec<usize>,
+    }
+
+    impl RenderBoxed for OldList {
+        fn render(self: Box<Self>) -> Element {
+            fragment(self.items.into_iter().map(Item))
+        }
+    }
+
+    #[test]
+    fn render_boxed_view_renders_the_old_signature() {
+        let old = RenderBoxedView(OldList { items: vec![0, 1] });
+        assert!(Render::render(&old) == fragment([Item(0), Item(1)]));
+    }
+}
@@ -1,23 +1,131 @@
 use super::*;
-use std::{
-    any::Any,
-    sync::{Arc, Mutex},
-};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
 
 pub struct Node {
     pub box_render: Box<dyn Render>,
-    pub platform_data: Arc<Mutex<Option<Box<dyn Any>>>>,
+    pub platform_data: Rc<RefCell<Option<Box<dyn Any>>>>,
+    event_handlers: Rc<RefCell<EventHandlers>>,
+    local_state: Rc<LocalStateSlot>,
+    dispatcher: Dispatcher,
 }
 
 impl Node {
-    fn on_mount(&self) {
-        self.box_render.on_mount();
+    fn new(
+        box_render: Box<dyn Render>,
+        event_handlers: EventHandlers,
+        parent: Option<&Node>,
+        context: &TreeContext,
+    ) -> Self {
+        Self {
+            box_render,
+            platform_data: Rc::new(RefCell::new(None)),
+            event_handlers: Rc::new(RefCell::new(event_handlers)),
+            local_state: Rc::new(LocalStateSlot::new(
+                parent.and_then(|parent| parent.local_state.child_contexts()),
+            )),
+            dispatcher: context.dispatcher.clone(),
+        }
+    }
+    /// Returns a listener that runs this node's current click handler
+    /// and dispatches the resulting event into the `start` loop.
+    pub fn click_listener(&self) -> impl Fn(ClickEvent) + 'static {
+        let event_handlers = self.event_handlers.clone();
+        let dispatcher = self.dispatcher.clone();
+        move |click_event| {
+            let event = event_handlers.borrow().on_click(click_event);
+            if let Some(event) = event {
+                dispatcher.dispatch_box(event);
+            }
+        }
+    }
+    fn mount(&self, context: &TreeContext, ancestors: &Vec<&Node>, next_sibling: Option<&Node>) {
+        report_panic(|| self.box_render.on_mount());
+        // The platform hook is not guarded: a platform node that failed to mount
+        // would leave its children to mount into the wrong ancestor.
+        (context.on_mount)(self, ancestors, next_sibling);
+    }
+    /// Children of a platform node are laid out inside it, so only the children of
+    /// a node without platform data share `next_sibling` with the node itself.
+    fn next_sibling_for_children<'a>(&self, next_sibling: Option<&'a Node>) -> Option<&'a Node> {
+        if self.platform_data.borrow().is_some() {
+            None
+        } else {
+            next_sibling
+        }
+    }
+    fn update_render(&mut self, box_render: Box<dyn Render>, context: &TreeContext) {
+        let old_box_render = std::mem::replace(&mut self.box_render, box_render);
+        (context.on_update)(old_box_render.as_ref(), self.box_render.as_ref(), self);
+    }
+    fn on_unmount(&self, context: &TreeContext) {
+        report_panic(|| self.box_render.on_unmount());
+        (context.on_unmount)(self);
+        self.local_state.clear();
     }
-    fn on_unmount(&self) {
-        self.box_render.on_unmount();
+    fn render(&self) -> Result<Vec<Element>, RenderError> {
+        let element = self.local_state.render(&self.dispatcher, || {
+            catch_panic(|| self.box_render.try_render()).and_then(|element| element)
+        })?;
+        Ok(match element {
+            Element::Multiple { elements } => Rc::unwrap_or_clone(elements),
+            element => vec![element],
+        })
+    }
+    /// If this node is an error boundary, runs `update` on its children and replaces them
+    /// with the fallback when it fails. Otherwise just runs `update`.
+    fn update_children_in_boundary(
+        &self,
+        children: &mut Vec<RenderTree>,
+        context: &TreeContext,
+        ancestors: &Vec<&Node>,
+        next_sibling: Option<&Node>,
+        update: impl FnOnce(&mut Vec<RenderTree>),
+    ) {
+        let Some(boundary) = self.box_render.as_any().downcast_ref::<ErrorBoundary>() else {
+            update(children);
+            return;
+        };
+        let ((), errors) = collect_errors(|| update(children));
+        let Some(error) = errors.into_iter().next() else {
+            return;
+        };
+
+        for child in children.drain(..) {
+            child.on_unmount(context);
+        }
+        let event = self.event_handlers.borrow().on_error(error);
+        if let Some(event) = event {
+            self.dispatcher.dispatch_box(event);
+        }
+        update_trees(
+            children,
+            vec![boundary.fallback.clone()],
+            context,
+            ancestors,
+            next_sibling,
+        );
     }
 }
 
+/// Platform hook called after a node is created, with its ancestors from the root down
+/// and the first platform node that will follow it under the same platform parent.
+pub type OnMount<'a> = dyn Fn(&Node, &Vec<&Node>, Option<&Node>) + 'a;
+/// Platform hook called when a node's props change, with the old and new props.
+pub type OnUpdate<'a> = dyn Fn(&dyn Render, &dyn Render, &Node) + 'a;
+/// Platform hook called when a node is removed from the tree.
+pub type OnUnmount<'a> = dyn Fn(&Node) + 'a;
+/// Platform hook called when a mounted node moves among its siblings,
+/// with the same arguments as `OnMount`.
+pub type OnMove<'a> = dyn Fn(&Node, &Vec<&Node>, Option<&Node>) + 'a;
+
+pub struct TreeContext<'a> {
+    pub on_mount: &'a OnMount<'a>,
+    pub on_update: &'a OnUpdate<'a>,
+    pub on_unmount: &'a OnUnmount<'a>,
+    pub on_move: &'a OnMove<'a>,
+    pub dispatcher: Dispatcher,
+}
+
 pub enum RenderTree {
     Single {
         node: Node,
@@ -26,29 +134,22 @@ pub enum RenderTree {
     Multiple {
         nodes: Vec<RenderTree>,
     },
+    Keyed {
+        key: String,
+        tree: Box<RenderTree>,
+    },
 }
 
 impl RenderTree {
     pub fn from_render(
         render: impl Render + PartialEq + Clone + 'static,
-        on_mount: &dyn Fn(&Node, &Vec<&Node>),
+        context: &TreeContext,
     ) -> RenderTree {
-        render.on_mount();
-
-        let mut node = Node {
-            box_render: Box::new(render),
-            platform_data: Arc::new(Mutex::new(None)),
-        };
-
-        on_mount(&node, &vec![]);
+        let node = Node::new(Box::new(render), EventHandlers::default(), None, context);
+        node.mount(context, &vec![], None);
 
         let mut children = vec![];
-        update_children(
-            &mut children,
-            node.box_render.clone_box(),
-            &on_mount,
-            &vec![&node],
-        );
+        update_children(&mut children, &node, context, &vec![&node], None);
 
         RenderTree::Single { node, children }
     }
@@ -56,255 +157,609 @@ impl RenderTree {
     pub fn update(
         &mut self,
         render: impl Render + PartialEq + Clone + 'static,
-        on_mount: &dyn Fn(&Node, &Vec<&Node>),
+        context: &TreeContext,
     ) {
-        let Self::Single{ node, children } = self else {
+        let Self::Single { node, .. } = self else {
             unreachable!()
         };
         if node.box_render.as_any().downcast_ref() == Some(&render) {
-            crate::log!(" # same props");
+            if any_dirty_slot() {
+                self.update_dirty_state(context, &vec![], None);
+            }
             return;
         }
 
         if node.box_render.as_any().type_id() != render.type_id() {
-            crate::log!(" # different type id");
-            *node = Node {
-                box_render: Box::new(render),
-                platform_data: Arc::new(Mutex::new(None)),
-            };
-            node.on_mount();
-            on_mount(&node, &vec![]);
-        } else {
-            crate::log!(" # same type id update props");
-            node.box_render = render.clone_box();
+            self.on_unmount(context);
+            *self = RenderTree::from_render(render, context);
+            return;
         }
 
-        update_children(
-            children,
-            node.box_render.clone_box(),
-            &on_mount,
-            &vec![&node],
-        );
+        let Self::Single { node, children } = self else {
+            unreachable!()
+        };
+        node.update_render(Box::new(render), context);
+
+        update_children(children, node, context, &vec![node], None);
     }
 
-    fn from_element<'a>(
+    fn from_element(
         element: Element,
-        on_mount: &dyn Fn(&Node, &Vec<&Node>),
+        context: &TreeContext,
         ancestors: &Vec<&Node>,
+        next_sibling: Option<&Node>,
     ) -> Self {
-        element.on_mount();
-
         match element {
-            Element::Single { box_render } => {
-                let node = Node {
+            Element::Single {
+                box_render,
+                event_handlers,
+            } => {
+                let node = Node::new(
                     box_render,
-                    platform_data: Arc::new(Mutex::new(None)),
-                };
-                on_mount(&node, &ancestors);
-                let children = {
-                    render_to_children(
-                        node.box_render.clone_box(),
-                        &on_mount,
-                        &ancestors
-                            .clone()
-                            .into_iter()
-                            .chain(std::iter::once::<&Node>(&node))
-                            .collect(),
-                    )
-                };
+                    event_handlers,
+                    ancestors.last().copied(),
+                    context,
+                );
+                node.mount(context, ancestors, next_sibling);
 
-                Self::Single {
-                    node,
-                    children,
-                }
+                let mut children = vec![];
+                update_children(
+                    &mut children,
+                    &node,
+                    context,
+                    &ancestors
+                        .clone()
+                        .into_iter()
+                        .chain(std::iter::once::<&Node>(&node))
+                        .collect(),
+                    node.next_sibling_for_children(next_sibling),
+                );
+
+                Self::Single { node, children }
             }
             Element::Multiple { elements } => {
-                let nodes = elements
-                    .into_iter()
-                    .map(|element| RenderTree::from_element(element, &on_mount, ancestors))
-                    .collect();
+                let mut nodes = vec![];
+                update_trees(
+                    &mut nodes,
+                    Rc::unwrap_or_clone(elements),
+                    context,
+                    ancestors,
+                    next_sibling,
+                );
 
                 Self::Multiple { nodes }
             }
+            Element::Keyed { key, element } => Self::Keyed {
+                key,
+                tree: Box::new(RenderTree::from_element(
+                    Rc::unwrap_or_clone(element),
+                    context,
+                    ancestors,
+                    next_sibling,
+                )),
+            },
         }
     }
 
-    fn update_by_element<'a>(
+    fn update_by_element(
         &mut self,
         element: Element,
-        on_mount: &dyn Fn(&Node, &Vec<&Node>),
+        context: &TreeContext,
         ancestors: &Vec<&Node>,
+        next_sibling: Option<&Node>,
     ) {
-        match (&self, element) {
+        match (self, element) {
             (
-                RenderTree::Single {
-                    node,
-                    children: _,
-                },
+                RenderTree::Single { node, children },
                 Element::Single {
                     box_render: element_box_render,
+                    event_handlers,
                 },
-            ) => {
+            ) if node.box_render.as_any().type_id() == element_box_render.as_any().type_id() => {
+                *node.event_handlers.borrow_mut() = event_handlers;
+
                 if node.box_render.equals(element_box_render.as_ref()) {
-                    crate::log!(" # same props");
+                    if any_dirty_slot() {
+                        update_dirty_node(node, children, context, ancestors, next_sibling);
+                    }
                     return;
                 }
 
-                let RenderTree::Single {
-                    node,
-                    children,
-                } = self else {
-                    unreachable!()
-                };
-
-                if node.box_render.as_any().type_id() != element_box_render.as_any().type_id() {
-                    crate::log!(" # different type id");
-                    node.on_unmount();
-                    
-                    *node = Node {
-                        box_render: element_box_render.clone_box(),
-                        platform_data: Arc::new(Mutex::new(None)),
-                    };
-                    node.on_mount();
-                    on_mount(&node, &ancestors);
-                } else {
-                    crate::log!(" # same type id update props");
-                    node.box_render = element_box_render.clone_box();
-                }
+                node.update_render(element_box_render, context);
 
                 update_children(
                     children,
-                    element_box_render,
-                    on_mount,
+                    node,
+                    context,
                     &ancestors
                         .clone()
                         .into_iter()
-                        .chain(std::iter::once::<&Node>(&node))
+                        .chain(std::iter::once::<&Node>(node))
                         .collect(),
+                    node.next_sibling_for_children(next_sibling),
                 );
             }
-            (RenderTree::Single { .. }, Element::Multiple { elements }) => {
-                self.on_unmount();
-
-                let nodes = elements
-                    .into_iter()
-                    .map(|element| RenderTree::from_element(element, on_mount, ancestors))
-                    .collect();
-
-                *self = RenderTree::Multiple { nodes };
+            (RenderTree::Multiple { nodes }, Element::Multiple { elements }) => {
+                update_trees(
+                    nodes,
+                    Rc::unwrap_or_clone(elements),
+                    context,
+                    ancestors,
+                    next_sibling,
+                );
             }
-            (RenderTree::Multiple { nodes: _ }, Element::Single { box_render }) => {
-                self.on_unmount();
-                let node = Node {
-                    box_render,
-                    platform_data: Arc::new(Mutex::new(None)),
-                };
-                node.on_mount();
-                on_mount(&node, &ancestors);
-
-                let children = {
-                    render_to_children(
-                        node.box_render.clone_box(),
-                        on_mount,
-                        &ancestors
-                            .clone()
-                            .into_iter()
-                            .chain(std::iter::once::<&Node>(&node))
-                            .collect(),
-                    )
-                };
+            (
+                RenderTree::Keyed { key, tree },
+                Element::Keyed {
+                    key: new_key,
+                    element,
+                },
+            ) if *key == new_key => {
+                tree.update_by_element(
+                    Rc::unwrap_or_clone(element),
+                    context,
+                    ancestors,
+                    next_sibling,
+                );
+            }
+            (this, element) => {
+                this.on_unmount(context);
+                *this = RenderTree::from_element(element, context, ancestors, next_sibling);
+            }
+        }
+    }
 
-                *self = RenderTree::Single { node, children };
+    /// Re-renders the components under this tree whose local state changed,
+    /// where the props above them did not.
+    fn update_dirty_state(
+        &mut self,
+        context: &TreeContext,
+        ancestors: &Vec<&Node>,
+        next_sibling: Option<&Node>,
+    ) {
+        match self {
+            RenderTree::Single { node, children } => {
+                update_dirty_node(node, children, context, ancestors, next_sibling);
             }
-            (RenderTree::Multiple { .. }, Element::Multiple { elements }) => {
-                let RenderTree::Multiple { nodes } = self else {
-                    unreachable!()
-                };
+            RenderTree::Multiple { nodes } => {
+                update_dirty_trees(nodes, context, ancestors, next_sibling);
+            }
+            RenderTree::Keyed { tree, .. } => {
+                tree.update_dirty_state(context, ancestors, next_sibling);
+            }
+        }
+    }
 
-                let max_index = std::cmp::max(nodes.len(), elements.len());
-
-                for (index, element) in elements.into_iter().enumerate() {
-                    let node = nodes.get_mut(index);
-                    match node {
-                        Some(node) => {
-                            node.update_by_element(element, on_mount, ancestors);
-                        }
-                        None => {
-                            nodes.push(RenderTree::from_element(element, on_mount, ancestors));
-                        }
-                    }
+    fn key(&self) -> Option<&str> {
+        match self {
+            RenderTree::Keyed { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// The outermost nodes of this tree that have platform data, in order.
+    fn top_platform_nodes(&self) -> Vec<&Node> {
+        match self {
+            RenderTree::Single { node, children } => {
+                if node.platform_data.borrow().is_some() {
+                    vec![node]
+                } else {
+                    children
+                        .iter()
+                        .flat_map(|child| child.top_platform_nodes())
+                        .collect()
                 }
+            }
+            RenderTree::Multiple { nodes } => nodes
+                .iter()
+                .flat_map(|node| node.top_platform_nodes())
+                .collect(),
+            RenderTree::Keyed { tree, .. } => tree.top_platform_nodes(),
+        }
+    }
 
-                for _ in max_index..nodes.len() {
-                    let node = nodes.pop().unwrap();
-                    node.on_unmount();
+    fn first_platform_node(&self) -> Option<&Node> {
+        match self {
+            RenderTree::Single { node, children } => {
+                if node.platform_data.borrow().is_some() {
+                    Some(node)
+                } else {
+                    children
+                        .iter()
+                        .find_map(|child| child.first_platform_node())
                 }
             }
+            RenderTree::Multiple { nodes } => {
+                nodes.iter().find_map(|node| node.first_platform_node())
+            }
+            RenderTree::Keyed { tree, .. } => tree.first_platform_node(),
         }
     }
 
-    fn on_unmount(&self) {
+    fn move_before(
+        &self,
+        context: &TreeContext,
+        ancestors: &Vec<&Node>,
+        next_sibling: Option<&Node>,
+    ) {
+        for node in self.top_platform_nodes() {
+            (context.on_move)(node, ancestors, next_sibling);
+        }
+    }
+
+    pub(crate) fn on_unmount(&self, context: &TreeContext) {
         match self {
             RenderTree::Single { node, children } => {
                 for child in children {
-                    child.on_unmount();
+                    child.on_unmount(context);
                 }
-                node.on_unmount();
+                node.on_unmount(context);
             }
             RenderTree::Multiple { nodes } => {
                 for node in nodes {
-                    node.on_unmount();
+                    node.on_unmount(context);
                 }
             }
+            RenderTree::Keyed { tree, .. } => tree.on_unmount(context),
         }
     }
 }
 
-fn update_children<'a>(
+fn update_children(
+    children: &mut Vec<RenderTree>,
+    node: &Node,
+    context: &TreeContext,
+    ancestors: &Vec<&Node>,
+    next_sibling: Option<&Node>,
+) {
+    node.update_children_in_boundary(children, context, ancestors, next_sibling, |children| {
+        let elements = node.render().unwrap_or_else(|error| {
+            report_error(error);
+            vec![]
+        });
+        update_trees(children, elements, context, ancestors, next_sibling);
+    });
+}
+
+fn update_dirty_node(
+    node: &Node,
     children: &mut Vec<RenderTree>,
-    render: Box<dyn Render>,
-    on_mount: &dyn Fn(&Node, &Vec<&Node>),
+    context: &TreeContext,
+    ancestors: &Vec<&Node>,
+    next_sibling: Option<&Node>,
+) {
+    let ancestors = ancestors
+        .iter()
+        .copied()
+        .chain(std::iter::once(node))
+        .collect();
+    let next_sibling = node.next_sibling_for_children(next_sibling);
+    if node.local_state.take_dirty() {
+        update_children(children, node, context, &ancestors, next_sibling);
+    } else {
+        node.update_children_in_boundary(children, context, &ancestors, next_sibling, |children| {
+            update_dirty_trees(children, context, &ancestors, next_sibling)
+        });
+    }
+}
+
+fn update_dirty_trees(
+    trees: &mut [RenderTree],
+    context: &TreeContext,
     ancestors: &Vec<&Node>,
+    next_sibling: Option<&Node>,
 ) {
-    #[allow(deprecated)]
-    let elements: Vec<Element> = render_to_elements(render);
+    for index in (0..trees.len()).rev() {
+        let (tree, following) = trees[index..].split_first_mut().unwrap();
+        let next_sibling = following
+            .iter()
+            .find_map(|tree| tree.first_platform_node())
+            .or(next_sibling);
+        tree.update_dirty_state(context, ancestors, next_sibling);
+    }
+}
 
-    let max_index = std::cmp::max(children.len(), elements.len());
+/// Reconciles `trees` against `elements`.
+///
+/// Keyed elements are matched to the old tree with the same key wherever it was,
+/// so inserting, removing or reordering keyed siblings never remounts the others.
+/// Unkeyed elements are matched by index against unkeyed old trees.
+///
+/// Siblings are visited from last to first so each one knows the platform node that follows it:
+/// new trees are mounted before it, and reused trees that are out of order are moved before it.
+fn update_trees(
+    trees: &mut Vec<RenderTree>,
+    elements: Vec<Element>,
+    context: &TreeContext,
+    ancestors: &Vec<&Node>,
+    next_sibling: Option<&Node>,
+) {
+    let mut old_trees: Vec<Option<RenderTree>> =
+        std::mem::take(trees).into_iter().map(Some).collect();
+    let old_index_by_key: HashMap<String, usize> = old_trees
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tree)| {
+            tree.as_ref()
+                .and_then(|tree| tree.key())
+                .map(|key| (key.to_string(), index))
+        })
+        .collect();
+
+    let matched = elements
+        .into_iter()
+        .enumerate()
+        .map(|(index, element)| {
+            let old_index = match element.key() {
+                Some(key) => old_index_by_key.get(key).copied(),
+                None => Some(index).filter(|index| {
+                    old_trees
+                        .get(*index)
+                        .and_then(|tree| tree.as_ref())
+                        .is_some_and(|tree| tree.key().is_none())
+                }),
+            };
+            let old_tree = old_index.and_then(|old_index| {
+                old_trees[old_index]
+                    .take()
+                    .map(|old_tree| (old_index, old_tree))
+            });
+            (element, old_tree)
+        })
+        .collect::<Vec<_>>();
+
+    for tree in old_trees.into_iter().flatten() {
+        tree.on_unmount(context);
+    }
 
-    for (index, element) in elements.into_iter().enumerate() {
-        let child = children.get_mut(index);
-        match child {
-            Some(child) => {
-                child.update_by_element(element, on_mount, ancestors);
+    let mut reversed_trees: Vec<RenderTree> = Vec::with_capacity(matched.len());
+    let mut min_staying_old_index = usize::MAX;
+
+    for (element, old_tree) in matched.into_iter().rev() {
+        let next_sibling = reversed_trees
+            .iter()
+            .rev()
+            .find_map(|tree| tree.first_platform_node())
+            .or(next_sibling);
+
+        let tree = match old_tree {
+            Some((old_index, mut tree)) => {
+                tree.update_by_element(element, context, ancestors, next_sibling);
+                if old_index < min_staying_old_index {
+                    min_staying_old_index = old_index;
+                } else {
+                    tree.move_before(context, ancestors, next_sibling);
+                }
+                tree
             }
-            None => {
-                children.push(RenderTree::from_element(element, on_mount, ancestors));
+            None => RenderTree::from_element(element, context, ancestors, next_sibling),
+        };
+        reversed_trees.push(tree);
+    }
+
+    reversed_trees.reverse();
+    *trees = reversed_trees;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    thread_local! {
+        static LIFECYCLE: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    }
+
+    fn take_lifecycle() -> Vec<String> {
+        LIFECYCLE.with(|lifecycle| std::mem::take(&mut *lifecycle.borrow_mut()))
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Row {
+        id: usize,
+    }
+
+    impl Render for Row {
+        fn render(&self) -> Element {
+            render(())
+        }
+        fn on_mount(&self) {
+            LIFECYCLE.with(|lifecycle| lifecycle.borrow_mut().push(format!("mount {}", self.id)));
+        }
+        fn on_unmount(&self) {
+            LIFECYCLE.with(|lifecycle| lifecycle.borrow_mut().push(format!("unmount {}", self.id)));
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct List {
+        ids: Vec<usize>,
+    }
+
+    impl Render for List {
+        fn render(&self) -> Element {
+            Element::Multiple {
+                elements: Rc::new(
+                    self.ids
+                        .iter()
+                        .copied()
+                        .map(|id| Element::keyed(id, Row { id }))
+                        .collect(),
+                ),
             }
         }
     }
 
-    for _ in max_index..children.len() {
-        let child = children.pop().unwrap();
-        child.on_unmount();
+    fn context() -> TreeContext<'static> {
+        TreeContext {
+            on_mount: &|_, _, _| {},
+            on_update: &|_, _, _| {},
+            on_unmount: &|_| {},
+            on_move: &|_, _, _| {},
+            dispatcher: Dispatcher::new(),
+        }
     }
-}
 
-fn render_to_elements(render: Box<dyn Render>) -> Vec<Element> {
-    #[allow(deprecated)]
-    match render.render() {
-        Element::Single { box_render } => vec![Element::Single { box_render }],
-        Element::Multiple { elements } => elements,
+    fn mount(ids: Vec<usize>) -> RenderTree {
+        let render_tree = RenderTree::from_render(List { ids }, &context());
+        take_lifecycle();
+        render_tree
     }
-}
 
-fn render_to_children<'a>(
-    render: Box<dyn Render>,
-    on_mount: &dyn Fn(&Node, &Vec<&Node>),
-    ancestors: &Vec<&Node>,
-) -> Vec<RenderTree> {
-    let elements = render_to_elements(render);
-    elements
-        .into_iter()
-        .map(|element| RenderTree::from_element(element, on_mount, ancestors))
-        .collect()
+    fn rendered_ids(render_tree: &RenderTree) -> Vec<usize> {
+        let RenderTree::Single { children, .. } = render_tree else {
+            unreachable!()
+        };
+        children
+            .iter()
+            .map(|child| {
+                let RenderTree::Keyed { tree, .. } = child else {
+                    unreachable!()
+                };
+                let RenderTree::Multiple { nodes } = tree.as_ref() else {
+                    unreachable!()
+                };
+                let RenderTree::Single { node, .. } = &nodes[0] else {
+                    unreachable!()
+                };
+                node.box_render.as_any().downcast_ref::<Row>().unwrap().id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keyed_insert_mounts_only_new_node() {
+        let mut render_tree = mount(vec![1, 2, 3]);
+
+        render_tree.update(
+            List {
+                ids: vec![0, 1, 2, 3],
+            },
+            &context(),
+        );
+
+        assert_eq!(take_lifecycle(), vec!["mount 0"]);
+        assert_eq!(rendered_ids(&render_tree), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn keyed_remove_unmounts_only_removed_node() {
+        let mut render_tree = mount(vec![1, 2, 3]);
+
+        render_tree.update(List { ids: vec![1, 3] }, &context());
+
+        assert_eq!(take_lifecycle(), vec!["unmount 2"]);
+        assert_eq!(rendered_ids(&render_tree), vec![1, 3]);
+    }
+
+    #[test]
+    fn keyed_reorder_does_not_remount() {
+        let mut render_tree = mount(vec![1, 2, 3]);
+
+        render_tree.update(List { ids: vec![3, 1, 2] }, &context());
+
+        assert_eq!(take_lifecycle(), Vec::<String>::new());
+        assert_eq!(rendered_ids(&render_tree), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn keyed_mixed_changes() {
+        let mut render_tree = mount(vec![1, 2, 3, 4]);
+
+        render_tree.update(List { ids: vec![4, 5, 2] }, &context());
+
+        assert_eq!(take_lifecycle(), vec!["unmount 1", "unmount 3", "mount 5"]);
+        assert_eq!(rendered_ids(&render_tree), vec![4, 5, 2]);
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Button {
+        label: &'static str,
+    }
+
+    impl Render for Button {
+        fn render(&self) -> Element {
+            let label = self.label;
+            render(Row { id: 0 }).event(move |builder| {
+                builder.on_click_fn(move |_| Some(label));
+            })
+        }
+    }
+
+    fn clicked_row(render_tree: &RenderTree) -> &Node {
+        let RenderTree::Single { children, .. } = render_tree else {
+            unreachable!()
+        };
+        let RenderTree::Single { node, .. } = &children[0] else {
+            unreachable!()
+        };
+        node
+    }
+
+    #[test]
+    fn click_handler_dispatches_and_is_replaced_on_update() {
+        let dispatcher = Dispatcher::new();
+        let mut rx = dispatcher.take_receiver().unwrap();
+        let context = TreeContext {
+            dispatcher,
+            ..context()
+        };
+        let mut render_tree = RenderTree::from_render(Button { label: "first" }, &context);
+
+        clicked_row(&render_tree).click_listener()(ClickEvent {});
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.downcast_ref::<&str>(), Some(&"first"));
+
+        render_tree.update(Button { label: "second" }, &context);
+
+        clicked_row(&render_tree).click_listener()(ClickEvent {});
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.downcast_ref::<&str>(), Some(&"second"));
+        assert_eq!(take_lifecycle(), vec!["mount 0"]);
+    }
+
+    thread_local! {
+        static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    #[derive(PartialEq)]
+    struct Counted {
+        value: usize,
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.with(|clones| clones.set(clones.get() + 1));
+            Counted { value: self.value }
+        }
+    }
+
+    impl Render for Counted {
+        fn render(&self) -> Element {
+            ().into_element()
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Pair {
+        value: usize,
+    }
+
+    impl Render for Pair {
+        fn render(&self) -> Element {
+            let value = self.value;
+            crate::render((
+                Counted { value },
+                Element::keyed(value, Counted { value }),
+                vec![Counted { value }],
+            ))
+        }
+    }
+
+    #[test]
+    fn fresh_children_are_moved_into_the_tree_without_cloning() {
+        let mut render_tree = RenderTree::from_render(Pair { value: 0 }, &context());
+        render_tree.update(Pair { value: 1 }, &context());
+
+        assert_eq!(CLONES.with(|clones| clones.get()), 0);
+    }
 }
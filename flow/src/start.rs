@@ -1,41 +1,356 @@
 use crate::*;
-use render_tree::{Node, RenderTree};
+use render_tree::{OnMount, OnMove, OnUnmount, OnUpdate, RenderTree, TreeContext};
+use std::{future::Future, pin::Pin, task::Poll};
 
-pub async fn start<'a, Model: Reduce, View: Render + PartialEq + Clone + 'static>(
-    mut model: Model,
+/// How a platform such as `start_dom` applies the view and schedules its updates.
+pub struct Platform<'a, WaitForFlush> {
+    pub on_mount: &'a OnMount<'a>,
+    pub on_update: &'a OnUpdate<'a>,
+    pub on_unmount: &'a OnUnmount<'a>,
+    pub on_move: &'a OnMove<'a>,
+    /// Called after the first event that changes the model since the last render.
+    /// The view is updated once the future it returns resolves.
+    pub wait_for_flush: WaitForFlush,
+}
+
+/// Runs the event loop until `dispatcher.shutdown()` is called.
+///
+/// Queued events are reduced in order as they arrive, but the view is only updated
+/// once the future returned by `platform.wait_for_flush` resolves, so a burst of events
+/// renders once.
+pub async fn start<
+    Model: Update<Kind>,
+    Kind,
+    View: Render + PartialEq + Clone + 'static,
+    Flush: Future<Output = ()>,
+>(
+    dispatcher: Dispatcher,
+    model: Model,
     to_view: impl Fn(&Model) -> View,
-    on_mount: &dyn Fn(&Node, &Vec<&Node>),
+    subscriptions: impl Fn(&Model) -> Vec<Subscription> + 'static,
+    platform: Platform<'_, impl Fn() -> Flush>,
 ) {
-    // TODO: Pass event to tx
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let Some(mut rx) = dispatcher.take_receiver() else {
+        crate::error!("The dispatcher is already used by another start loop");
+        return;
+    };
+    let Platform {
+        on_mount,
+        on_update,
+        on_unmount,
+        on_move,
+        wait_for_flush,
+    } = platform;
+    let context = TreeContext {
+        on_mount,
+        on_update,
+        on_unmount,
+        on_move,
+        dispatcher,
+    };
 
-    let mut render_tree: Option<RenderTree> = None;
-    let view = to_view(&model);
-    update_view(&mut render_tree, view, on_mount);
+    let mut runtime = Runtime::new(model, to_view, Box::new(subscriptions));
+    runtime.update_view(&context);
 
+    let mut flush: Option<Pin<Box<Flush>>> = None;
     loop {
-        let event: Box<dyn std::any::Any> = rx.recv().await.unwrap();
-        println!("\n\n# event: {:?}", event);
+        // Queued events come before the flush, so everything dispatched by now renders together.
+        let next = std::future::poll_fn(|cx| {
+            runtime.poll_effects(cx, &context.dispatcher);
+            if let Poll::Ready(event) = rx.poll_recv(cx) {
+                return Poll::Ready(match event {
+                    Some(event) if !event.is::<Shutdown>() => Next::Event(event),
+                    _ => Next::Shutdown,
+                });
+            }
+            match flush.as_mut() {
+                Some(flush) => flush.as_mut().poll(cx).map(|()| Next::Flush),
+                None => Poll::Pending,
+            }
+        })
+        .await;
+
+        match next {
+            Next::Event(event) => {
+                if runtime.reduce(event, &context) && flush.is_none() {
+                    flush = Some(Box::pin(wait_for_flush()));
+                }
+            }
+            Next::Shutdown => break,
+            Next::Flush => {
+                flush = None;
+                runtime.update_view(&context);
+            }
+        }
+    }
+
+    runtime.shutdown(&context);
+}
 
-        model = model.reduce(event.as_ref());
+enum Next {
+    Event(Box<dyn std::any::Any>),
+    Flush,
+    Shutdown,
+}
 
-        let view = to_view(&model);
-        update_view(&mut render_tree, view, on_mount);
+pub(crate) type ToSubscriptions<Model> = Box<dyn Fn(&Model) -> Vec<Subscription>>;
+
+/// Model, view function and render tree shared by every platform's event loop.
+pub(crate) struct Runtime<Model, ToView> {
+    model: Option<Model>,
+    to_view: ToView,
+    render_tree: Option<RenderTree>,
+    effects: Effects,
+    to_subscriptions: ToSubscriptions<Model>,
+    subscriptions: Subscriptions,
+}
+
+impl<Model, View: Render + PartialEq + Clone + 'static, ToView: Fn(&Model) -> View>
+    Runtime<Model, ToView>
+{
+    pub(crate) fn new(
+        model: Model,
+        to_view: ToView,
+        to_subscriptions: ToSubscriptions<Model>,
+    ) -> Self {
+        Self {
+            model: Some(model),
+            to_view,
+            render_tree: None,
+            effects: Effects::default(),
+            to_subscriptions,
+            subscriptions: Subscriptions::default(),
+        }
+    }
+
+    pub(crate) fn model(&self) -> &Model {
+        self.model
+            .as_ref()
+            .expect("The model was lost to a panicking reducer")
+    }
+
+    /// Reduces `event` and runs the commands it requested, without updating the view.
+    /// Returns whether the model changed.
+    ///
+    /// A reducer that panics is logged and the commands it requested are dropped.
+    /// If it took the model with it, the loop is shut down, since there is nothing left to render.
+    /// Panics are only caught on native targets; they abort on `wasm32`.
+    pub(crate) fn reduce<Kind>(
+        &mut self,
+        event: Box<dyn std::any::Any>,
+        context: &TreeContext,
+    ) -> bool
+    where
+        Model: Update<Kind>,
+    {
+        let (changed, commands) = collect_commands(|| {
+            catch_panic(|| match event.downcast_ref::<LocalEvent>() {
+                Some(local_event) => local_event.reduce(),
+                None => Model::update(&mut self.model, event.as_ref()),
+            })
+        });
+        let changed = match changed {
+            Ok(changed) => changed,
+            Err(error) => {
+                crate::error!("Reducer panicked: {}", error);
+                if self.model.is_none() {
+                    context.dispatcher.shutdown();
+                }
+                return false;
+            }
+        };
+        for cmd in commands {
+            self.effects.run(cmd, &context.dispatcher);
+        }
+        changed
+    }
+
+    /// Polls pending effects and subscription streams, dispatching the events they produced.
+    /// Returns whether there were any.
+    pub(crate) fn poll_effects(
+        &mut self,
+        cx: &mut std::task::Context,
+        dispatcher: &Dispatcher,
+    ) -> bool {
+        let resolved = self.effects.poll(cx, dispatcher);
+        self.subscriptions.poll(cx, dispatcher) || resolved
+    }
+
+    pub(crate) fn pending_effects(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub(crate) fn subscription_keys(&self) -> Vec<&str> {
+        self.subscriptions.keys()
+    }
+
+    /// Unmounts the view, stops every subscription and drops pending effects.
+    pub(crate) fn shutdown(&mut self, context: &TreeContext) {
+        if let Some(render_tree) = self.render_tree.take() {
+            render_tree.on_unmount(context);
+        }
+        self.subscriptions.update(vec![], &context.dispatcher);
+        self.effects = Effects::default();
+    }
+
+    /// Rebuilds the view and the subscriptions from the current model.
+    pub(crate) fn update_view(&mut self, context: &TreeContext) {
+        if self.model.is_none() {
+            return;
+        }
+        let subscriptions = (self.to_subscriptions)(self.model());
+        self.subscriptions
+            .update(subscriptions, &context.dispatcher);
+
+        let view = (self.to_view)(self.model());
+        match self.render_tree.as_mut() {
+            Some(render_tree) => {
+                render_tree.update(view, context);
+            }
+            None => {
+                self.render_tree = Some(RenderTree::from_render(view, context));
+            }
+        }
     }
 }
 
-fn update_view<'a>(
-    render_tree: &mut Option<RenderTree>,
-    view: impl Render + PartialEq + Clone + 'static,
-    on_mount: &dyn Fn(&Node, &Vec<&Node>),
-) {
-    println!("update_view");
-    match render_tree.as_mut() {
-        Some(render_tree) => {
-            render_tree.update(view, &on_mount);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use render_tree::Node;
+    use std::{
+        any::Any,
+        cell::{Cell, RefCell},
+        rc::Rc,
+        task::{Context, Waker},
+    };
+    use tokio::sync::oneshot;
+
+    #[derive(Clone)]
+    struct Counter(usize);
+
+    struct Increment;
+
+    impl Reduce for Counter {
+        fn reduce(self, event: &dyn Any) -> Self {
+            match event.downcast_ref::<Increment>() {
+                Some(Increment) => Counter(self.0 + 1),
+                None => self,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct CounterView(usize);
+
+    impl Render for CounterView {
+        fn render(&self) -> Element {
+            ().into_element()
+        }
+    }
+
+    /// Drives `start` by hand: every `wait_for_flush` future resolves only when `flush` is called.
+    struct Harness {
+        dispatcher: Dispatcher,
+        views: Rc<RefCell<Vec<usize>>>,
+        flushes: Rc<RefCell<Vec<oneshot::Sender<()>>>>,
+        mounted: Rc<Cell<usize>>,
+        stopped: Rc<Cell<bool>>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self {
+                dispatcher: Dispatcher::new(),
+                views: Rc::default(),
+                flushes: Rc::default(),
+                mounted: Rc::default(),
+                stopped: Rc::default(),
+            }
+        }
+
+        fn flush(&self) {
+            for flush in self.flushes.borrow_mut().drain(..) {
+                flush.send(()).unwrap();
+            }
         }
-        None => {
-            *render_tree = Some(RenderTree::from_render(view, &on_mount));
+
+        async fn start(&self) {
+            let views = self.views.clone();
+            let flushes = self.flushes.clone();
+            let stopped = self.stopped.clone();
+            let on_mount = |_: &Node, _: &Vec<&Node>, _: Option<&Node>| {
+                self.mounted.set(self.mounted.get() + 1)
+            };
+            let on_unmount = |_: &Node| self.mounted.set(self.mounted.get() - 1);
+            start(
+                self.dispatcher.clone(),
+                Counter(0),
+                move |model: &Counter| {
+                    views.borrow_mut().push(model.0);
+                    CounterView(model.0)
+                },
+                move |_: &Counter| {
+                    let stopped = stopped.clone();
+                    vec![Subscription::new(
+                        "ticks",
+                        move |_: Rc<dyn Fn(Increment)>| move || stopped.set(true),
+                    )]
+                },
+                Platform {
+                    on_mount: &on_mount,
+                    on_update: &|_, _, _| {},
+                    on_unmount: &on_unmount,
+                    on_move: &|_, _, _| {},
+                    wait_for_flush: || {
+                        let (tx, rx) = oneshot::channel();
+                        flushes.borrow_mut().push(tx);
+                        async move { rx.await.unwrap() }
+                    },
+                },
+            )
+            .await
+        }
+    }
+
+    #[test]
+    fn burst_of_events_renders_once_the_flush_resolves() {
+        let harness = Harness::new();
+        let mut start = std::pin::pin!(harness.start());
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(start.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(*harness.views.borrow(), [0]);
+
+        for _ in 0..3 {
+            harness.dispatcher.dispatch(Increment);
         }
+        assert!(start.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(*harness.views.borrow(), [0]);
+        assert_eq!(harness.flushes.borrow().len(), 1);
+
+        harness.dispatcher.dispatch(Increment);
+        harness.flush();
+        assert!(start.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(*harness.views.borrow(), [0, 4]);
+        assert!(harness.flushes.borrow().is_empty());
+    }
+
+    #[test]
+    fn shutdown_unmounts_the_tree_and_ends_the_loop() {
+        let harness = Harness::new();
+        let mut start = std::pin::pin!(harness.start());
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(start.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(harness.mounted.get(), 1);
+        assert!(!harness.stopped.get());
+
+        harness.dispatcher.dispatch(Increment);
+        harness.dispatcher.shutdown();
+        assert!(start.as_mut().poll(&mut cx).is_ready());
+        assert_eq!(harness.mounted.get(), 0);
+        assert!(harness.stopped.get());
+        assert_eq!(*harness.views.borrow(), [0]);
     }
 }
@@ -0,0 +1,204 @@
+use crate::Dispatcher;
+use futures_core::Stream;
+use std::{
+    any::Any,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+type EventStream = Pin<Box<dyn Stream<Item = Box<dyn Any>>>>;
+type Start = Box<dyn FnOnce(&Dispatcher) -> Box<dyn FnOnce()>>;
+
+/// An external source of events, such as a timer or a browser event, derived from the model.
+///
+/// After every model change the loop compares the subscriptions by key:
+/// new keys are started and keys that disappeared are cancelled.
+pub struct Subscription {
+    key: String,
+    kind: SubscriptionKind,
+}
+
+enum SubscriptionKind {
+    Callback(Start),
+    Stream(EventStream),
+}
+
+impl Subscription {
+    /// `start` receives a function that dispatches events and returns a function that stops them.
+    pub fn new<Event: Any, Stop: FnOnce() + 'static>(
+        key: impl ToString,
+        start: impl FnOnce(Rc<dyn Fn(Event)>) -> Stop + 'static,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            kind: SubscriptionKind::Callback(Box::new(move |dispatcher: &Dispatcher| {
+                let dispatcher = dispatcher.clone();
+                let stop = start(Rc::new(move |event: Event| {
+                    dispatcher.dispatch_box(Box::new(event))
+                }));
+                Box::new(stop)
+            })),
+        }
+    }
+
+    /// Dispatches every item of `stream` until it ends or the subscription is cancelled.
+    pub fn stream<Event: Any>(
+        key: impl ToString,
+        stream: impl Stream<Item = Event> + 'static,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            kind: SubscriptionKind::Stream(Box::pin(BoxedItems(Box::pin(stream)))),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+struct BoxedItems<S>(Pin<Box<S>>);
+
+impl<S: Stream> Stream for BoxedItems<S>
+where
+    S::Item: Any,
+{
+    type Item = Box<dyn Any>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .0
+            .as_mut()
+            .poll_next(cx)
+            .map(|item| item.map(|item| Box::new(item) as Box<dyn Any>))
+    }
+}
+
+enum Running {
+    Callback(Box<dyn FnOnce()>),
+    /// `None` once the stream has ended, so it is not restarted while its key stays.
+    Stream(Option<EventStream>),
+}
+
+/// Subscriptions started by the loop, by key.
+#[derive(Default)]
+pub(crate) struct Subscriptions {
+    running: Vec<(String, Running)>,
+}
+
+impl Subscriptions {
+    pub(crate) fn update(&mut self, subscriptions: Vec<Subscription>, dispatcher: &Dispatcher) {
+        let mut previous = std::mem::take(&mut self.running);
+        for subscription in subscriptions {
+            if self.running.iter().any(|(key, _)| *key == subscription.key) {
+                crate::error!("Duplicate subscription key: {}", subscription.key);
+                continue;
+            }
+            let running = match previous
+                .iter()
+                .position(|(key, _)| *key == subscription.key)
+            {
+                Some(index) => previous.swap_remove(index).1,
+                None => match subscription.kind {
+                    SubscriptionKind::Callback(start) => Running::Callback(start(dispatcher)),
+                    SubscriptionKind::Stream(stream) => Running::Stream(Some(stream)),
+                },
+            };
+            self.running.push((subscription.key, running));
+        }
+
+        for (_, running) in previous {
+            if let Running::Callback(stop) = running {
+                stop();
+            }
+        }
+    }
+
+    /// Dispatches at most one ready item per stream. Returns whether there were any.
+    ///
+    /// A stream that always has an item ready would otherwise starve the loop,
+    /// so the task is woken to poll again after the dispatched events are handled.
+    pub(crate) fn poll(&mut self, cx: &mut Context, dispatcher: &Dispatcher) -> bool {
+        let mut dispatched = false;
+        for (_, running) in &mut self.running {
+            let Running::Stream(Some(stream)) = running else {
+                continue;
+            };
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    dispatcher.dispatch_box(event);
+                    dispatched = true;
+                }
+                Poll::Ready(None) => *running = Running::Stream(None),
+                Poll::Pending => {}
+            }
+        }
+        if dispatched {
+            cx.waker().wake_by_ref();
+        }
+        dispatched
+    }
+
+    pub(crate) fn keys(&self) -> Vec<&str> {
+        self.running.iter().map(|(key, _)| key.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Waker;
+
+    /// Always has the next number ready, until `end`.
+    struct Count {
+        next: usize,
+        end: usize,
+    }
+
+    impl Stream for Count {
+        type Item = usize;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<usize>> {
+            if self.next == self.end {
+                return Poll::Ready(None);
+            }
+            self.next += 1;
+            Poll::Ready(Some(self.next - 1))
+        }
+    }
+
+    fn received(rx: &mut crate::dispatcher::Receiver) -> Vec<usize> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|event| *event.downcast::<usize>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn poll_dispatches_one_item_per_stream() {
+        let dispatcher = Dispatcher::new();
+        let mut rx = dispatcher.take_receiver().unwrap();
+        let mut subscriptions = Subscriptions::default();
+        let mut cx = Context::from_waker(Waker::noop());
+        subscriptions.update(
+            vec![
+                Subscription::stream(
+                    "endless",
+                    Count {
+                        next: 0,
+                        end: usize::MAX,
+                    },
+                ),
+                Subscription::stream("once", Count { next: 10, end: 11 }),
+            ],
+            &dispatcher,
+        );
+
+        assert!(subscriptions.poll(&mut cx, &dispatcher));
+        assert_eq!(received(&mut rx), [0, 10]);
+
+        assert!(subscriptions.poll(&mut cx, &dispatcher));
+        assert_eq!(received(&mut rx), [1]);
+        assert_eq!(subscriptions.keys(), ["endless", "once"]);
+    }
+}